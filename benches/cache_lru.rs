@@ -0,0 +1,142 @@
+//! LRU 性能对比：`cargo bench --bench cache_lru`
+//!
+//! 对比 O(1) 的 `lru_cache` 与旧版 `VecDeque` 记录顺序的实现（每次命中线性扫描）。
+
+use rovkit::cachekit::{lru_cache, Cache};
+use std::collections::{BTreeMap, VecDeque};
+use std::hint::black_box;
+use std::time::Instant;
+
+const ENTRIES: u64 = 100_000;
+const OPS: u64 = 10_000;
+
+/// 旧版实现，仅用于对比
+struct ScanLru {
+    map: BTreeMap<u64, u64>,
+    order: VecDeque<u64>,
+    cap: usize,
+}
+
+impl ScanLru {
+    fn touch(&mut self, key: u64) {
+        if let Some(pos) = self.order.iter().position(|k| *k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key);
+    }
+}
+
+impl Cache<u64, u64> for ScanLru {
+    fn get(&mut self, key: &u64) -> Option<&u64> {
+        if self.map.contains_key(key) {
+            self.touch(*key);
+        }
+        self.map.get(key)
+    }
+
+    fn put(&mut self, key: u64, value: u64) {
+        if self.map.insert(key, value).is_some() {
+            self.touch(key);
+            return;
+        }
+        if self.map.len() > self.cap {
+            if let Some(lru) = self.order.pop_front() {
+                self.map.remove(&lru);
+            }
+        }
+        self.order.push_back(key);
+    }
+
+    fn remove(&mut self, key: &u64) -> Option<u64> {
+        self.order.retain(|k| k != key);
+        self.map.remove(key)
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn peek(&self, key: &u64) -> Option<&u64> {
+        self.map.get(key)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&u64, &u64)> + '_> {
+        Box::new(self.map.iter())
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.order.clear();
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&u64, &u64) -> bool) {
+        self.map.retain(|k, v| f(k, v));
+        let map = &self.map;
+        self.order.retain(|k| map.contains_key(k));
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        self.cap = capacity;
+        while self.map.len() > self.cap {
+            match self.order.pop_front() {
+                Some(lru) => self.map.remove(&lru),
+                None => break,
+            };
+        }
+    }
+}
+
+/// 伪随机 key，避免引入额外依赖
+fn key(i: u64) -> u64 {
+    i.wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407)
+        % ENTRIES
+}
+
+fn bench(name: &str, cache: &mut dyn Cache<u64, u64>) {
+    let start = Instant::now();
+    for i in 0..ENTRIES {
+        cache.put(i, i);
+    }
+    let fill = start.elapsed();
+
+    let start = Instant::now();
+    for i in 0..OPS {
+        black_box(cache.get(&key(i)));
+    }
+    let hits = start.elapsed();
+
+    let start = Instant::now();
+    for i in 0..OPS {
+        cache.put(ENTRIES + i, i);
+    }
+    let evictions = start.elapsed();
+
+    println!(
+        "{:<10} fill {:>8.2?}  get {:>10.1} ns/op  put+evict {:>10.1} ns/op",
+        name,
+        fill,
+        hits.as_nanos() as f64 / OPS as f64,
+        evictions.as_nanos() as f64 / OPS as f64,
+    );
+}
+
+fn main() {
+    println!("{} entries, {} ops", ENTRIES, OPS);
+    bench(
+        "lru_cache",
+        lru_cache::<u64, u64>(ENTRIES as usize).as_mut(),
+    );
+    bench(
+        "scan_lru",
+        &mut ScanLru {
+            map: BTreeMap::new(),
+            order: VecDeque::new(),
+            cap: ENTRIES as usize,
+        },
+    );
+}
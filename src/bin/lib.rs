@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 mod demo_env;
 mod demo_id;
 mod demo_job;
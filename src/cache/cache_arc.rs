@@ -0,0 +1,171 @@
+use crate::cache::cache_stats::StatsCounter;
+use crate::cachekit::{Cache, CacheStats, RemovalCause};
+use std::hash::Hash;
+
+/// ARC（Adaptive Replacement Cache）：
+/// `t1` 存只访问过一次的 key，`t2` 存访问过多次的 key，
+/// `b1`、`b2` 记录最近从 `t1`、`t2` 淘汰的 key（只存 key），
+/// 命中这些记录时调整 `t1` 的目标大小 `p`，在偏重最近访问和偏重访问频率之间自适应。
+pub struct ArcCache<K, V> {
+    t1: lru::LruCache<K, V>,
+    t2: lru::LruCache<K, V>,
+    b1: lru::LruCache<K, ()>,
+    b2: lru::LruCache<K, ()>,
+    p: usize,
+    cap: usize,
+    stats: StatsCounter,
+}
+
+impl<K: Hash + Eq + Clone, V> ArcCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            t1: lru::LruCache::unbounded(),
+            t2: lru::LruCache::unbounded(),
+            b1: lru::LruCache::unbounded(),
+            b2: lru::LruCache::unbounded(),
+            p: 0,
+            cap: capacity,
+            stats: StatsCounter::default(),
+        }
+    }
+
+    /// 缓存满时从 `t1` 或 `t2` 淘汰一个 key 到对应的记录表
+    fn replace(&mut self, in_b2: bool) {
+        if self.t1.len() + self.t2.len() < self.cap {
+            return;
+        }
+        let t1_len = self.t1.len();
+        if t1_len > 0 && (t1_len > self.p || (in_b2 && t1_len == self.p)) {
+            if let Some((key, _)) = self.t1.pop_lru() {
+                self.b1.put(key, ());
+            }
+        } else if let Some((key, _)) = self.t2.pop_lru() {
+            self.b2.put(key, ());
+        } else if let Some((key, _)) = self.t1.pop_lru() {
+            self.b1.put(key, ());
+        } else {
+            return;
+        }
+        self.stats.removal(RemovalCause::Capacity);
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for ArcCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        if let Some(value) = self.t1.pop(key) {
+            self.t2.put(key.clone(), value);
+        }
+        self.stats.lookup(self.t2.get(key))
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        if self.cap == 0 {
+            return;
+        }
+        if self.t1.pop(&key).is_some() || self.t2.contains(&key) {
+            self.t2.put(key, value);
+            self.stats.removal(RemovalCause::Replaced);
+            return;
+        }
+
+        if self.b1.contains(&key) {
+            let delta = (self.b2.len() / self.b1.len()).max(1);
+            self.p = (self.p + delta).min(self.cap);
+            self.replace(false);
+            self.b1.pop(&key);
+            self.t2.put(key, value);
+            return;
+        }
+        if self.b2.contains(&key) {
+            let delta = (self.b1.len() / self.b2.len()).max(1);
+            self.p = self.p.saturating_sub(delta);
+            self.replace(true);
+            self.b2.pop(&key);
+            self.t2.put(key, value);
+            return;
+        }
+
+        let l1 = self.t1.len() + self.b1.len();
+        let total = l1 + self.t2.len() + self.b2.len();
+        if l1 >= self.cap {
+            if self.t1.len() < self.cap {
+                self.b1.pop_lru();
+                self.replace(false);
+            } else if self.t1.pop_lru().is_some() {
+                self.stats.removal(RemovalCause::Capacity);
+            }
+        } else if total >= self.cap {
+            if total >= 2 * self.cap {
+                self.b2.pop_lru();
+            }
+            self.replace(false);
+        }
+        self.t1.put(key, value);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.b1.pop(key);
+        self.b2.pop(key);
+        let value = self.t1.pop(key).or_else(|| self.t2.pop(key))?;
+        self.stats.removal(RemovalCause::Explicit);
+        Some(value)
+    }
+
+    fn len(&self) -> usize {
+        self.t1.len() + self.t2.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.t1.peek(key).or_else(|| self.t2.peek(key))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        Box::new(self.t1.iter().chain(self.t2.iter()))
+    }
+
+    /// 同时清空淘汰记录
+    fn clear(&mut self) {
+        self.stats.removals(RemovalCause::Explicit, self.len());
+        self.t1.clear();
+        self.t2.clear();
+        self.b1.clear();
+        self.b2.clear();
+        self.p = 0;
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &V) -> bool) {
+        let keys: Vec<K> = self
+            .iter()
+            .filter(|(key, value)| !f(key, value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            Cache::remove(self, &key);
+        }
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        self.cap = capacity;
+        self.p = self.p.min(capacity);
+        while self.len() > self.cap {
+            self.replace(false);
+        }
+        while self.b1.len() + self.b2.len() > self.cap {
+            if self.b1.pop_lru().is_none() {
+                self.b2.pop_lru();
+            }
+        }
+    }
+
+    fn record_stats(&mut self) {
+        self.stats.enable();
+    }
+
+    fn stats(&self) -> Option<CacheStats> {
+        self.stats.snapshot()
+    }
+}
@@ -0,0 +1,332 @@
+use crate::cache::cache_stats::StatsCounter;
+use crate::cachekit::{Cache, CacheStats, EvictionListener, ExpiringCache, RemovalCause};
+use crate::clockkit::{self, Clock};
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 计算条目权重
+pub type Weigher<K, V> = dyn Fn(&K, &V) -> u64 + Send + Sync;
+
+/// 组合条目数、总权重、TTL、TTI 的缓存构建器
+///
+/// ```
+/// use rovkit::cachekit::{Cache, CacheBuilder};
+/// use std::time::Duration;
+///
+/// let mut cache = CacheBuilder::new()
+///     .max_entries(1000)
+///     .max_weight(1 << 20)
+///     .weigher(|_k: &String, v: &Vec<u8>| v.len() as u64)
+///     .time_to_live(Duration::from_secs(60))
+///     .time_to_idle(Duration::from_secs(10))
+///     .build();
+/// cache.put("k".to_string(), vec![0; 16]);
+/// assert_eq!(cache.weight(), 16);
+/// ```
+pub struct CacheBuilder<K, V> {
+    max_entries: Option<usize>,
+    max_weight: Option<u64>,
+    weigher: Option<Arc<Weigher<K, V>>>,
+    ttl: Option<Duration>,
+    tti: Option<Duration>,
+    clock: Arc<dyn Clock>,
+    listener: Option<Arc<EvictionListener<K, V>>>,
+    record_stats: bool,
+}
+
+impl<K: Hash + Eq + Clone, V> CacheBuilder<K, V> {
+    pub fn new() -> Self {
+        Self {
+            max_entries: None,
+            max_weight: None,
+            weigher: None,
+            ttl: None,
+            tti: None,
+            clock: clockkit::system(),
+            listener: None,
+            record_stats: false,
+        }
+    }
+
+    /// 最大条目数，超出时淘汰最久未使用的
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// 最大总权重，超出时淘汰最久未使用的，没有设置 `weigher` 时每个条目权重为 1
+    pub fn max_weight(mut self, max_weight: u64) -> Self {
+        self.max_weight = Some(max_weight);
+        self
+    }
+
+    /// 设置权重函数
+    pub fn weigher(mut self, weigher: impl Fn(&K, &V) -> u64 + Send + Sync + 'static) -> Self {
+        self.weigher = Some(Arc::new(weigher));
+        self
+    }
+
+    /// 写入后存活时间
+    pub fn time_to_live(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// 最后一次访问后的存活时间
+    pub fn time_to_idle(mut self, tti: Duration) -> Self {
+        self.tti = Some(tti);
+        self
+    }
+
+    /// 使用指定时钟判断过期
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 设置移除回调，过期、淘汰、删除、覆盖时都会调用
+    pub fn eviction_listener(
+        mut self,
+        listener: impl Fn(&K, &V, RemovalCause) + Send + Sync + 'static,
+    ) -> Self {
+        self.listener = Some(Arc::new(listener));
+        self
+    }
+
+    /// 开启统计，见 `Cache::stats`
+    pub fn record_stats(mut self) -> Self {
+        self.record_stats = true;
+        self
+    }
+
+    pub fn build(self) -> ComposedCache<K, V> {
+        let stats = StatsCounter::default();
+        if self.record_stats {
+            stats.enable();
+        }
+        ComposedCache {
+            entries: lru::LruCache::unbounded(),
+            weight: 0,
+            max_entries: self.max_entries,
+            max_weight: self.max_weight,
+            weigher: self.weigher,
+            ttl: self.ttl,
+            tti: self.tti,
+            clock: self.clock,
+            listener: self.listener,
+            stats,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Default for CacheBuilder<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct ComposedEntry<V> {
+    value: V,
+    weight: u64,
+    /// 由 TTL 或单条覆盖的 TTL 决定，和 `last_access` 一样是时钟的单调时间
+    expires_at: Option<Duration>,
+    last_access: Duration,
+}
+
+/// `CacheBuilder` 构建的缓存，按最久未使用淘汰，过期条目在访问或 `purge_expired` 时移除
+pub struct ComposedCache<K, V> {
+    entries: lru::LruCache<K, ComposedEntry<V>>,
+    weight: u64,
+    max_entries: Option<usize>,
+    max_weight: Option<u64>,
+    weigher: Option<Arc<Weigher<K, V>>>,
+    ttl: Option<Duration>,
+    tti: Option<Duration>,
+    clock: Arc<dyn Clock>,
+    listener: Option<Arc<EvictionListener<K, V>>>,
+    stats: StatsCounter,
+}
+
+impl<K: Hash + Eq + Clone, V> ComposedCache<K, V> {
+    /// 写入并单独指定存活时间，覆盖构建时的 TTL
+    pub fn put_with_ttl(&mut self, key: K, value: V, ttl: Duration) {
+        self.insert(key, value, Some(ttl));
+    }
+
+    /// 当前总权重
+    pub fn weight(&self) -> u64 {
+        self.weight
+    }
+
+    fn insert(&mut self, key: K, value: V, ttl: Option<Duration>) {
+        let weight = match &self.weigher {
+            Some(weigher) => weigher(&key, &value),
+            None => 1,
+        };
+        if let Some(old) = self.entries.pop(&key) {
+            self.weight -= old.weight;
+            self.notify(&key, &old.value, RemovalCause::Replaced);
+        }
+        // 单个条目超出总权重上限时不缓存
+        if self.max_entries == Some(0) || self.max_weight.is_some_and(|max| weight > max) {
+            return;
+        }
+
+        let now = self.clock.monotonic();
+        let expires_at = ttl.and_then(|ttl| now.checked_add(ttl));
+        self.entries.put(
+            key,
+            ComposedEntry {
+                value,
+                weight,
+                expires_at,
+                last_access: now,
+            },
+        );
+        self.weight += weight;
+        self.evict();
+    }
+
+    /// 淘汰最久未使用的条目，直到不超过条目数和总权重上限
+    fn evict(&mut self) {
+        while self.max_entries.is_some_and(|max| self.entries.len() > max)
+            || self.max_weight.is_some_and(|max| self.weight > max)
+        {
+            match self.entries.pop_lru() {
+                Some((key, entry)) => {
+                    self.weight -= entry.weight;
+                    self.notify(&key, &entry.value, RemovalCause::Capacity);
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn notify(&self, key: &K, value: &V, cause: RemovalCause) {
+        self.stats.removal(cause);
+        if let Some(listener) = &self.listener {
+            listener(key, value, cause);
+        }
+    }
+
+    fn take(&mut self, key: &K, cause: RemovalCause) -> Option<V> {
+        let entry = self.entries.pop(key)?;
+        self.weight -= entry.weight;
+        self.notify(key, &entry.value, cause);
+        Some(entry.value)
+    }
+
+    fn is_expired(&self, entry: &ComposedEntry<V>, now: Duration) -> bool {
+        entry.expires_at.is_some_and(|at| now >= at)
+            || self
+                .tti
+                .is_some_and(|tti| now.saturating_sub(entry.last_access) >= tti)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for ComposedCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        let now = self.clock.monotonic();
+        let Some(entry) = self.entries.peek(key) else {
+            self.stats.miss();
+            return None;
+        };
+        if self.is_expired(entry, now) {
+            self.take(key, RemovalCause::Expired);
+            self.stats.miss();
+            return None;
+        }
+        self.stats.hit();
+        let entry = self.entries.get_mut(key)?;
+        entry.last_access = now;
+        Some(&entry.value)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        self.insert(key, value, self.ttl);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.take(key, RemovalCause::Explicit)
+    }
+
+    /// 包含尚未清理的过期条目，见 `purge_expired`
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 最大条目数，未设置时为 `usize::MAX`
+    fn capacity(&self) -> usize {
+        self.max_entries.unwrap_or(usize::MAX)
+    }
+
+    /// 不更新最后访问时间
+    fn peek(&self, key: &K) -> Option<&V> {
+        let now = self.clock.monotonic();
+        self.entries
+            .peek(key)
+            .filter(|entry| !self.is_expired(entry, now))
+            .map(|entry| &entry.value)
+    }
+
+    /// 从最近使用到最久未使用
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        let now = self.clock.monotonic();
+        Box::new(
+            self.entries
+                .iter()
+                .filter(move |(_, entry)| !self.is_expired(entry, now))
+                .map(|(key, entry)| (key, &entry.value)),
+        )
+    }
+
+    fn clear(&mut self) {
+        while let Some((key, entry)) = self.entries.pop_lru() {
+            self.notify(&key, &entry.value, RemovalCause::Explicit);
+        }
+        self.weight = 0;
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &V) -> bool) {
+        let keys: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(key, entry)| !f(key, &entry.value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            self.take(&key, RemovalCause::Explicit);
+        }
+    }
+
+    /// 修改最大条目数
+    fn resize(&mut self, capacity: usize) {
+        self.max_entries = Some(capacity);
+        self.evict();
+    }
+
+    fn record_stats(&mut self) {
+        self.stats.enable();
+    }
+
+    fn stats(&self) -> Option<CacheStats> {
+        self.stats.snapshot()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> ExpiringCache for ComposedCache<K, V> {
+    fn purge_expired(&mut self) -> usize {
+        let now = self.clock.monotonic();
+        let expired: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| self.is_expired(entry, now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            self.take(key, RemovalCause::Expired);
+        }
+        expired.len()
+    }
+}
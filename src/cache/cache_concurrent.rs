@@ -0,0 +1,145 @@
+use crate::cache::cache_fifo::FifoCache;
+use crate::cache::cache_lru::LruCache;
+use crate::cache::cache_time::TimedCache;
+use crate::cachekit::{Cache, CacheStats, ConcurrentCache};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::sync::Mutex;
+
+/// 分片内部使用的单线程缓存
+pub(crate) trait Shard<K, V>: Send {
+    fn get(&mut self, key: &K) -> Option<&V>;
+    fn put(&mut self, key: K, value: V);
+    fn remove(&mut self, key: &K) -> Option<V>;
+    fn len(&self) -> usize;
+    fn capacity(&self) -> usize;
+    fn record_stats(&mut self);
+    fn stats(&self) -> Option<CacheStats>;
+}
+
+macro_rules! cache_shard {
+    ($cache:ident, $($bound:path),+) => {
+        impl<K: $($bound +)+ Send, V: Send> Shard<K, V> for $cache<K, V> {
+            fn get(&mut self, key: &K) -> Option<&V> {
+                Cache::get(self, key)
+            }
+
+            fn put(&mut self, key: K, value: V) {
+                Cache::put(self, key, value)
+            }
+
+            fn remove(&mut self, key: &K) -> Option<V> {
+                Cache::remove(self, key)
+            }
+
+            fn len(&self) -> usize {
+                Cache::len(self)
+            }
+
+            fn capacity(&self) -> usize {
+                Cache::capacity(self)
+            }
+
+            fn record_stats(&mut self) {
+                Cache::record_stats(self)
+            }
+
+            fn stats(&self) -> Option<CacheStats> {
+                Cache::stats(self)
+            }
+        }
+    };
+}
+
+cache_shard!(FifoCache, Eq, Hash, Clone);
+cache_shard!(LruCache, Eq, Hash);
+cache_shard!(TimedCache, Eq, Hash, Clone);
+
+/// 每个分片的最小容量
+const MIN_SHARD_CAPACITY: usize = 16;
+
+/// 分片加锁的线程安全缓存，key 按哈希分到各分片，不同分片的读写互不阻塞。
+/// 淘汰在分片内进行，容量按分片均分，所以 FIFO、LRU 的淘汰顺序是近似的。
+pub struct ShardedCache<S> {
+    shards: Vec<Mutex<S>>,
+    hasher: RandomState,
+}
+
+impl<S> ShardedCache<S> {
+    /// 创建 `shards` 个分片，第 i 个分片由 `new_shard(i)` 创建
+    pub(crate) fn new(shards: usize, new_shard: impl Fn(usize) -> S) -> Self {
+        Self {
+            shards: (0..shards.max(1))
+                .map(|i| Mutex::new(new_shard(i)))
+                .collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// 默认分片数：CPU 数的 4 倍取 2 的幂，每个分片至少 `MIN_SHARD_CAPACITY` 个条目。
+    /// key 在分片间分布不均，分片太小时容量没满就会淘汰，小缓存只用一个分片
+    pub(crate) fn default_shards(capacity: usize) -> usize {
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+        (cpus * 4)
+            .next_power_of_two()
+            .min(capacity / MIN_SHARD_CAPACITY)
+            .max(1)
+    }
+
+    /// 把 `capacity` 均分到 `shards` 个分片，返回第 `i` 个分片的容量
+    pub(crate) fn shard_capacity(capacity: usize, shards: usize, i: usize) -> usize {
+        capacity / shards + usize::from(i < capacity % shards)
+    }
+
+    fn shard<K: Hash>(&self, key: &K) -> &Mutex<S> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+}
+
+impl<K, V, S> ConcurrentCache<K, V> for ShardedCache<S>
+where
+    K: Hash,
+    V: Clone,
+    S: Shard<K, V>,
+{
+    fn get(&self, key: &K) -> Option<V> {
+        self.shard(key).lock().unwrap().get(key).cloned()
+    }
+
+    fn put(&self, key: K, value: V) {
+        self.shard(&key).lock().unwrap().put(key, value)
+    }
+
+    fn remove(&self, key: &K) -> Option<V> {
+        self.shard(key).lock().unwrap().remove(key)
+    }
+
+    fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.lock().unwrap().len()).sum()
+    }
+
+    fn capacity(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|s| s.lock().unwrap().capacity())
+            .sum()
+    }
+
+    fn record_stats(&self) {
+        for shard in &self.shards {
+            shard.lock().unwrap().record_stats();
+        }
+    }
+
+    /// 各分片统计之和
+    fn stats(&self) -> Option<CacheStats> {
+        let mut total: Option<CacheStats> = None;
+        for shard in &self.shards {
+            if let Some(stats) = shard.lock().unwrap().stats() {
+                total.get_or_insert_with(CacheStats::default).merge(&stats);
+            }
+        }
+        total
+    }
+}
@@ -0,0 +1,462 @@
+use crate::cachekit::{Cache, CacheStats};
+use crate::hashkit;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+/// 索引中的一条记录，`hash` 是 key 序列化后的 SHA-256，也是数据文件名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DiskEntry {
+    hash: String,
+    size: u64,
+    /// 数据文件内容的 SHA-256，读取时校验
+    checksum: String,
+}
+
+/// 索引日志的一行：写入（移到最近使用）或删除
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum IndexOp {
+    Put(DiskEntry),
+    Remove { removed: String },
+}
+
+/// 日志行数超过条目数两倍且不少于该值时压缩
+const MIN_COMPACT_LINES: usize = 64;
+
+fn invalid_data(e: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// 磁盘缓存：目录下每个 key 一个 `<hash>.data` 文件存 JSON 序列化的值，
+/// `index.jsonl` 按最久未使用在前的顺序记录所有条目。
+/// 总字节数超过 `max_bytes` 时淘汰最久未使用的文件。
+/// 索引是追加写的日志，写入和删除各追加一行，行数过多时压缩重写；
+/// 读取只调整内存中的顺序，在压缩、`flush` 和释放时保存。
+pub struct DiskCache<K, V> {
+    dir: PathBuf,
+    entries: lru::LruCache<String, DiskEntry>,
+    bytes: u64,
+    max_bytes: u64,
+    /// 索引文件当前的行数
+    log_lines: usize,
+    _marker: PhantomData<fn(K) -> V>,
+}
+
+impl<K, V> DiskCache<K, V> {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 所有数据文件的总字节数
+    pub fn size_bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 把索引压缩重写到磁盘，先写临时文件再重命名
+    pub fn flush(&mut self) -> io::Result<()> {
+        let path = self.index_path();
+        let tmp = path.with_extension("jsonl.tmp");
+        {
+            let mut file = File::create(&tmp)?;
+            for (_, entry) in self.entries.iter().rev() {
+                let line = serde_json::to_string(entry).map_err(invalid_data)?;
+                writeln!(file, "{}", line)?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(tmp, path)?;
+        self.log_lines = self.entries.len();
+        Ok(())
+    }
+
+    /// 追加到索引日志，不 fsync：崩溃丢失的行在下次打开时按数据文件是否存在修正
+    fn append(&mut self, ops: &[IndexOp]) -> io::Result<()> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for op in ops {
+            lines.push_str(&serde_json::to_string(op).map_err(invalid_data)?);
+            lines.push('\n');
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.index_path())?
+            .write_all(lines.as_bytes())?;
+        self.log_lines += ops.len();
+        if self.log_lines > (self.entries.len() * 2).max(MIN_COMPACT_LINES) {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("index.jsonl")
+    }
+
+    fn data_path(&self, hash: &str) -> PathBuf {
+        self.dir.join(format!("{}.data", hash))
+    }
+}
+
+impl<K: Serialize, V: Serialize + DeserializeOwned> DiskCache<K, V> {
+    /// 打开 `dir` 下的缓存，目录不存在时创建。
+    /// 索引里文件已丢失的记录会被丢弃，不在索引里的数据文件会被删除
+    pub fn open<P: Into<PathBuf>>(dir: P, max_bytes: u64) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        let mut cache = Self {
+            dir,
+            entries: lru::LruCache::unbounded(),
+            bytes: 0,
+            max_bytes,
+            log_lines: 0,
+            _marker: PhantomData,
+        };
+
+        for op in cache.read_index()? {
+            match op {
+                IndexOp::Put(entry) => {
+                    cache.entries.put(entry.hash.clone(), entry);
+                }
+                IndexOp::Remove { removed } => {
+                    cache.entries.pop(&removed);
+                }
+            }
+        }
+        let missing: Vec<String> = cache
+            .entries
+            .iter()
+            .filter(|(hash, _)| !cache.data_path(hash).is_file())
+            .map(|(hash, _)| hash.clone())
+            .collect();
+        for hash in missing {
+            cache.entries.pop(&hash);
+        }
+        cache.bytes = cache.entries.iter().map(|(_, entry)| entry.size).sum();
+
+        for file in fs::read_dir(&cache.dir)? {
+            let path = file?.path();
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("");
+            // 崩溃时留下的临时文件
+            let leftover = name.ends_with(".data.tmp") || name.ends_with(".jsonl.tmp");
+            let orphan = path.extension().is_some_and(|ext| ext == "data")
+                && path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .is_none_or(|hash| !cache.entries.contains(hash));
+            if leftover || orphan {
+                fs::remove_file(path)?;
+            }
+        }
+        cache.evict()?;
+        cache.flush()?;
+        Ok(cache)
+    }
+
+    pub fn get(&mut self, key: &K) -> io::Result<Option<V>> {
+        let hash = Self::hash(key)?;
+        let Some(expected) = self.entries.get(&hash).map(|e| e.checksum.clone()) else {
+            return Ok(None);
+        };
+        // 文件被外部删除或损坏时当作未命中，只读一次，在内存中校验
+        let data = match fs::read_to_string(self.data_path(&hash)) {
+            Ok(data) if hashkit::sha256(&data) == expected => Some(data),
+            Ok(_) => None,
+            Err(e) if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::InvalidData) => {
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        let Some(data) = data else {
+            self.remove_logged(&hash)?;
+            return Ok(None);
+        };
+        serde_json::from_str(&data).map(Some).map_err(invalid_data)
+    }
+
+    /// 写入后超出 `max_bytes` 时淘汰最久未使用的条目，单个值超过 `max_bytes` 时不写入
+    pub fn put(&mut self, key: &K, value: &V) -> io::Result<()> {
+        let hash = Self::hash(key)?;
+        let data = serde_json::to_string(value).map_err(invalid_data)?;
+        let size = data.len() as u64;
+        if size > self.max_bytes {
+            self.remove_logged(&hash)?;
+            return Ok(());
+        }
+
+        let path = self.data_path(&hash);
+        let tmp = path.with_extension("data.tmp");
+        {
+            let mut file = File::create(&tmp)?;
+            file.write_all(data.as_bytes())?;
+            file.sync_all()?;
+        }
+        fs::rename(tmp, path)?;
+
+        let checksum = hashkit::sha256(&data);
+        let entry = DiskEntry {
+            hash: hash.clone(),
+            size,
+            checksum,
+        };
+        if let Some(old) = self.entries.put(hash, entry.clone()) {
+            self.bytes -= old.size;
+        }
+        self.bytes += size;
+        let mut ops = vec![IndexOp::Put(entry)];
+        ops.extend(
+            self.evict()?
+                .into_iter()
+                .map(|removed| IndexOp::Remove { removed }),
+        );
+        self.append(&ops)
+    }
+
+    pub fn remove(&mut self, key: &K) -> io::Result<bool> {
+        self.remove_logged(&Self::hash(key)?)
+    }
+
+    pub fn contains_key(&self, key: &K) -> io::Result<bool> {
+        Ok(self.entries.contains(&Self::hash(key)?))
+    }
+
+    /// 删除所有条目和数据文件
+    pub fn clear(&mut self) -> io::Result<()> {
+        while let Some((hash, _)) = self.entries.pop_lru() {
+            Self::remove_file(&self.data_path(&hash))?;
+        }
+        self.bytes = 0;
+        self.flush()
+    }
+
+    fn hash(key: &K) -> io::Result<String> {
+        let key = serde_json::to_string(key).map_err(invalid_data)?;
+        Ok(hashkit::sha256(&key))
+    }
+
+    /// 索引按最久未使用在前的顺序保存，之后追加的写入和删除按顺序重放。
+    /// 崩溃时写了一半的行被跳过
+    fn read_index(&self) -> io::Result<Vec<IndexOp>> {
+        let file = match File::open(self.index_path()) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(op) => entries.push(op),
+                Err(e) => log::warn!("[Disk Cache Error] bad index line: {}", e),
+            }
+        }
+        Ok(entries)
+    }
+
+    fn remove_file(path: &Path) -> io::Result<()> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn remove_hash(&mut self, hash: &str) -> io::Result<bool> {
+        let Some(entry) = self.entries.pop(hash) else {
+            return Ok(false);
+        };
+        self.bytes -= entry.size;
+        Self::remove_file(&self.data_path(hash))?;
+        Ok(true)
+    }
+
+    /// 删除并记入索引日志
+    fn remove_logged(&mut self, hash: &str) -> io::Result<bool> {
+        let removed = self.remove_hash(hash)?;
+        if removed {
+            self.append(&[IndexOp::Remove {
+                removed: hash.to_string(),
+            }])?;
+        }
+        Ok(removed)
+    }
+
+    /// 返回被淘汰的条目
+    fn evict(&mut self) -> io::Result<Vec<String>> {
+        let mut evicted = Vec::new();
+        while self.bytes > self.max_bytes {
+            let Some((hash, entry)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.bytes -= entry.size;
+            Self::remove_file(&self.data_path(&hash))?;
+            evicted.push(hash);
+        }
+        Ok(evicted)
+    }
+}
+
+impl<K, V> Drop for DiskCache<K, V> {
+    /// 保存读取时调整过的顺序
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            log::warn!("[Disk Cache Error] {}", e);
+        }
+    }
+}
+
+/// 两级缓存：内存缓存在前，磁盘缓存在后。
+/// 写入同时写两层，内存未命中时从磁盘读取并放回内存，磁盘错误只记录日志。
+/// `remove`、`clear` 作用于两层；`peek`、`iter`、`retain` 只作用于内存层：
+/// 磁盘索引只存 key 的哈希，无法判断只在磁盘上的条目，`retain` 之后它们仍会被 `get` 读回，
+/// 需要删除时用 `remove` 或 `clear`
+pub struct TieredCache<K, V> {
+    memory: Box<dyn Cache<K, V>>,
+    disk: DiskCache<K, V>,
+}
+
+impl<K, V> TieredCache<K, V>
+where
+    K: Serialize + Clone,
+    V: Serialize + DeserializeOwned,
+{
+    pub fn new(memory: Box<dyn Cache<K, V>>, disk: DiskCache<K, V>) -> Self {
+        Self { memory, disk }
+    }
+
+    pub fn memory(&self) -> &dyn Cache<K, V> {
+        self.memory.as_ref()
+    }
+
+    pub fn disk(&self) -> &DiskCache<K, V> {
+        &self.disk
+    }
+}
+
+impl<K, V> Cache<K, V> for TieredCache<K, V>
+where
+    K: Serialize + Clone,
+    V: Serialize + DeserializeOwned,
+{
+    fn get(&mut self, key: &K) -> Option<&V> {
+        if self.memory.get(key).is_none() {
+            match self.disk.get(key) {
+                Ok(Some(value)) => self.memory.put(key.clone(), value),
+                Ok(None) => return None,
+                Err(e) => {
+                    log::warn!("[Disk Cache Error] {}", e);
+                    return None;
+                }
+            }
+        }
+        self.memory.get(key)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        if let Err(e) = self.disk.put(&key, &value) {
+            log::warn!("[Disk Cache Error] {}", e);
+        }
+        self.memory.put(key, value);
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let value = match self.memory.remove(key) {
+            Some(value) => Some(value),
+            None => self.disk.get(key).unwrap_or_else(|e| {
+                log::warn!("[Disk Cache Error] {}", e);
+                None
+            }),
+        };
+        if let Err(e) = self.disk.remove(key) {
+            log::warn!("[Disk Cache Error] {}", e);
+        }
+        value
+    }
+
+    /// 磁盘层的条目数，内存层是它的子集
+    fn len(&self) -> usize {
+        self.disk.len()
+    }
+
+    /// 磁盘层只限制字节数，没有条目数上限
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
+
+    /// 只读内存层
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.memory.peek(key)
+    }
+
+    /// 磁盘层按索引判断，不读取文件
+    fn contains_key(&self, key: &K) -> bool {
+        self.memory.contains_key(key) || self.disk.contains_key(key).unwrap_or(false)
+    }
+
+    /// 只遍历内存层，磁盘上的值需要反序列化，不在这里读取
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        self.memory.iter()
+    }
+
+    fn clear(&mut self) {
+        self.memory.clear();
+        if let Err(e) = self.disk.clear() {
+            log::warn!("[Disk Cache Error] {}", e);
+        }
+    }
+
+    /// 只按内存层的条目判断，删除的条目在磁盘上一并删除；
+    /// 只在磁盘上的条目不受影响，之后仍能被 `get` 读回
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &V) -> bool) {
+        let mut removed = Vec::new();
+        self.memory.retain(&mut |key, value| {
+            let keep = f(key, value);
+            if !keep {
+                removed.push(key.clone());
+            }
+            keep
+        });
+        for key in &removed {
+            if let Err(e) = self.disk.remove(key) {
+                log::warn!("[Disk Cache Error] {}", e);
+            }
+        }
+    }
+
+    /// 修改内存层容量，磁盘层由 `max_bytes` 限制
+    fn resize(&mut self, capacity: usize) {
+        self.memory.resize(capacity);
+    }
+
+    /// 统计内存层
+    fn record_stats(&mut self) {
+        self.memory.record_stats();
+    }
+
+    fn stats(&self) -> Option<CacheStats> {
+        self.memory.stats()
+    }
+}
@@ -1,11 +1,13 @@
+use crate::cache::cache_stats::StatsCounter;
+use crate::cachekit::{Cache, CacheStats, RemovalCause};
 use std::collections::{HashMap, VecDeque};
 use std::hash::Hash;
-use crate::cachekit::Cache;
 
 pub struct FifoCache<K, V> {
     map: HashMap<K, V>,
     order: VecDeque<K>,
     cap: usize,
+    stats: StatsCounter,
 }
 
 impl<K: Eq + Hash + Clone, V> FifoCache<K, V> {
@@ -14,24 +16,27 @@ impl<K: Eq + Hash + Clone, V> FifoCache<K, V> {
             map: HashMap::new(),
             order: VecDeque::new(),
             cap: capacity,
+            stats: StatsCounter::default(),
         }
     }
 }
 
 impl<K: Eq + Hash + Clone, V> Cache<K, V> for FifoCache<K, V> {
     fn get(&mut self, key: &K) -> Option<&V> {
-        self.map.get(key)
+        self.stats.lookup(self.map.get(key))
     }
 
     fn put(&mut self, key: K, value: V) {
         if self.map.contains_key(&key) {
             self.map.insert(key.clone(), value);
+            self.stats.removal(RemovalCause::Replaced);
             return;
         }
 
         if self.map.len() >= self.cap {
             if let Some(oldest) = self.order.pop_front() {
                 self.map.remove(&oldest);
+                self.stats.removal(RemovalCause::Capacity);
             }
         }
 
@@ -41,7 +46,9 @@ impl<K: Eq + Hash + Clone, V> Cache<K, V> for FifoCache<K, V> {
 
     fn remove(&mut self, key: &K) -> Option<V> {
         self.order.retain(|k| k != key);
-        self.map.remove(key)
+        let value = self.map.remove(key)?;
+        self.stats.removal(RemovalCause::Explicit);
+        Some(value)
     }
 
     fn len(&self) -> usize {
@@ -51,4 +58,53 @@ impl<K: Eq + Hash + Clone, V> Cache<K, V> for FifoCache<K, V> {
     fn capacity(&self) -> usize {
         self.cap
     }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.map.get(key)
+    }
+
+    /// 按写入顺序遍历
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        Box::new(
+            self.order
+                .iter()
+                .filter_map(|key| self.map.get_key_value(key)),
+        )
+    }
+
+    fn clear(&mut self) {
+        self.stats.removals(RemovalCause::Explicit, self.map.len());
+        self.map.clear();
+        self.order.clear();
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &V) -> bool) {
+        let before = self.map.len();
+        self.map.retain(|key, value| f(key, value));
+        self.stats
+            .removals(RemovalCause::Explicit, before - self.map.len());
+        let map = &self.map;
+        self.order.retain(|key| map.contains_key(key));
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        self.cap = capacity;
+        while self.map.len() > self.cap {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    self.map.remove(&oldest);
+                    self.stats.removal(RemovalCause::Capacity);
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn record_stats(&mut self) {
+        self.stats.enable();
+    }
+
+    fn stats(&self) -> Option<CacheStats> {
+        self.stats.snapshot()
+    }
 }
@@ -0,0 +1,167 @@
+use crate::cache::cache_stats::StatsCounter;
+use crate::cachekit::{Cache, CacheStats, RemovalCause};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+struct LfuEntry<V> {
+    value: V,
+    freq: u64,
+}
+
+/// O(1) 的 LFU：按访问次数分桶，淘汰次数最少的 key，次数相同时淘汰最久未使用的。
+/// `resize` 缩容时连续淘汰，需要重新找最小的桶
+pub struct LfuCache<K, V> {
+    entries: HashMap<K, LfuEntry<V>>,
+    /// 访问次数 -> 该次数下的 key，按使用先后排序
+    buckets: HashMap<u64, lru::LruCache<K, ()>>,
+    /// 不大于任何 key 的访问次数；对应的桶存在时就是最小的桶。
+    /// `remove` 后可能指向已删除的桶，缓存满之前必然有新 key 插入把它重置为 1
+    min_freq: u64,
+    cap: usize,
+    stats: StatsCounter,
+}
+
+impl<K: Hash + Eq + Clone, V> LfuCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            buckets: HashMap::new(),
+            min_freq: 0,
+            cap: capacity,
+            stats: StatsCounter::default(),
+        }
+    }
+
+    /// 访问次数加一，移到下一个桶
+    fn touch(&mut self, key: &K) {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return;
+        };
+        let freq = entry.freq;
+        entry.freq += 1;
+
+        if let Some(bucket) = self.buckets.get_mut(&freq) {
+            bucket.pop(key);
+            if bucket.is_empty() {
+                self.buckets.remove(&freq);
+                if self.min_freq == freq {
+                    self.min_freq = freq + 1;
+                }
+            }
+        }
+        self.buckets
+            .entry(freq + 1)
+            .or_insert_with(lru::LruCache::unbounded)
+            .put(key.clone(), ());
+    }
+
+    fn evict(&mut self) {
+        if !self.buckets.contains_key(&self.min_freq) {
+            // 只有 resize 连续淘汰时会走到这里
+            self.min_freq = self.buckets.keys().copied().min().unwrap_or(0);
+        }
+        let Some(bucket) = self.buckets.get_mut(&self.min_freq) else {
+            return;
+        };
+        if let Some((key, _)) = bucket.pop_lru() {
+            if bucket.is_empty() {
+                self.buckets.remove(&self.min_freq);
+            }
+            self.entries.remove(&key);
+            self.stats.removal(RemovalCause::Capacity);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for LfuCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.touch(key);
+        self.stats
+            .lookup(self.entries.get(key).map(|entry| &entry.value))
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        if self.cap == 0 {
+            return;
+        }
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.value = value;
+            self.touch(&key);
+            self.stats.removal(RemovalCause::Replaced);
+            return;
+        }
+
+        if self.entries.len() >= self.cap {
+            self.evict();
+        }
+        self.buckets
+            .entry(1)
+            .or_insert_with(lru::LruCache::unbounded)
+            .put(key.clone(), ());
+        self.entries.insert(key, LfuEntry { value, freq: 1 });
+        self.min_freq = 1;
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        if let Some(bucket) = self.buckets.get_mut(&entry.freq) {
+            bucket.pop(key);
+            if bucket.is_empty() {
+                self.buckets.remove(&entry.freq);
+            }
+        }
+        self.stats.removal(RemovalCause::Explicit);
+        Some(entry.value)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|entry| &entry.value)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        Box::new(self.entries.iter().map(|(key, entry)| (key, &entry.value)))
+    }
+
+    fn clear(&mut self) {
+        self.stats
+            .removals(RemovalCause::Explicit, self.entries.len());
+        self.entries.clear();
+        self.buckets.clear();
+        self.min_freq = 0;
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &V) -> bool) {
+        let keys: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(key, entry)| !f(key, &entry.value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            Cache::remove(self, &key);
+        }
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        self.cap = capacity;
+        while self.entries.len() > self.cap {
+            self.evict();
+        }
+    }
+
+    fn record_stats(&mut self) {
+        self.stats.enable();
+    }
+
+    fn stats(&self) -> Option<CacheStats> {
+        self.stats.snapshot()
+    }
+}
@@ -0,0 +1,288 @@
+use crate::cache::cache_stats::StatsCounter;
+use crate::cachekit::{
+    concurrent_lru_cache, concurrent_time_cache, CacheStats, ConcurrentCache, RemovalCause,
+};
+use crate::clockkit::{self, Clock};
+use crate::internal::gate::Gate;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// 后台刷新用的加载函数
+pub type RefreshFn<K, V> = dyn Fn(&K) -> Result<V, String> + Send + Sync;
+
+#[derive(Clone)]
+struct Loaded<V> {
+    value: V,
+    /// 时钟的单调时间
+    loaded_at: Duration,
+}
+
+/// 每个 key 一把锁，同一 key 的并发加载只有一个真正执行
+struct Flights<K, L> {
+    locks: Mutex<HashMap<K, Arc<L>>>,
+}
+
+impl<K: Hash + Eq + Clone, L: Default> Flights<K, L> {
+    fn new() -> Self {
+        Self {
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn acquire(&self, key: &K) -> Flight<'_, K, L> {
+        let mut locks = self.locks.lock().unwrap_or_else(PoisonError::into_inner);
+        let lock = locks.entry(key.clone()).or_default().clone();
+        Flight {
+            flights: self,
+            key: key.clone(),
+            lock,
+        }
+    }
+}
+
+/// 持有某个 key 的锁，drop 时（包括加载函数 panic 或 future 被取消）没有其他调用方等待就删除这把锁
+struct Flight<'a, K: Hash + Eq, L> {
+    flights: &'a Flights<K, L>,
+    key: K,
+    lock: Arc<L>,
+}
+
+impl<K: Hash + Eq, L> Drop for Flight<'_, K, L> {
+    fn drop(&mut self) {
+        let mut locks = self
+            .flights
+            .locks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if Arc::strong_count(&self.lock) == 2 {
+            locks.remove(&self.key);
+        }
+    }
+}
+
+/// 后台刷新结束（包括 panic）时把 key 移出刷新集合
+struct Refreshing<K: Hash + Eq> {
+    keys: Arc<Mutex<HashSet<K>>>,
+    key: K,
+}
+
+impl<K: Hash + Eq> Drop for Refreshing<K> {
+    fn drop(&mut self) {
+        self.keys
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.key);
+    }
+}
+
+/// 带加载函数的线程安全缓存（LRU 淘汰）。
+/// 同一 key 并发未命中时只有一个调用方执行加载，其余调用方等待并复用结果；
+/// 加载失败不会缓存，等待的调用方会依次重试。
+/// 设置 `refresh_ahead` 后，条目存在超过刷新时间时读取仍返回旧值，同时在后台重新加载。
+pub struct LoadingCache<K, V> {
+    inner: Arc<dyn ConcurrentCache<K, Loaded<V>>>,
+    /// 同步和异步加载共用，同一 key 不论从哪个接口加载都只执行一次
+    flights: Flights<K, Gate>,
+    ttl: Option<Duration>,
+    refresh: Option<(Duration, Arc<RefreshFn<K, V>>)>,
+    refreshing: Arc<Mutex<HashSet<K>>>,
+    clock: Arc<dyn Clock>,
+    stats: Arc<StatsCounter>,
+}
+
+impl<K, V> LoadingCache<K, V>
+where
+    K: 'static + Clone + Hash + Eq + Send + Sync,
+    V: 'static + Clone + Send + Sync,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self::with_inner(concurrent_lru_cache(capacity))
+    }
+
+    /// 不限容量，条目加载 `ttl_secs` 秒后过期，写入时清理所在分片已过期的条目
+    pub fn timed(ttl_secs: u64) -> Self {
+        Self::with_inner(concurrent_time_cache(ttl_secs))
+            .time_to_live(Duration::from_secs(ttl_secs))
+    }
+
+    fn with_inner(inner: Arc<dyn ConcurrentCache<K, Loaded<V>>>) -> Self {
+        Self {
+            inner,
+            flights: Flights::new(),
+            ttl: None,
+            refresh: None,
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+            clock: clockkit::system(),
+            stats: Arc::new(StatsCounter::default()),
+        }
+    }
+
+    /// 加载后的存活时间
+    pub fn time_to_live(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// 条目存在超过 `refresh_after` 后，读取时用 `loader` 在后台刷新
+    pub fn refresh_ahead(
+        mut self,
+        refresh_after: Duration,
+        loader: impl Fn(&K) -> Result<V, String> + Send + Sync + 'static,
+    ) -> Self {
+        self.refresh = Some((refresh_after, Arc::new(loader)));
+        self
+    }
+
+    /// 使用指定时钟判断过期和刷新
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 开启统计，见 `stats`
+    pub fn record_stats(self) -> Self {
+        self.stats.enable();
+        self.inner.record_stats();
+        self
+    }
+
+    /// 统计快照，没有开启统计时为 `None`。
+    /// 加载包括 `get_or_load` 系列和后台刷新，容量淘汰和覆盖来自底层 LRU
+    pub fn stats(&self) -> Option<CacheStats> {
+        let mut stats = self.stats.snapshot()?;
+        if let Some(inner) = self.inner.stats() {
+            stats.capacity_evicted = inner.capacity_evicted;
+            stats.replaced = inner.replaced;
+        }
+        Some(stats)
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.stats.lookup(self.lookup(key))
+    }
+
+    /// 不计入命中统计的读取
+    fn lookup(&self, key: &K) -> Option<V> {
+        let loaded = self.inner.get(key)?;
+        let age = self.clock.monotonic().saturating_sub(loaded.loaded_at);
+        if self.ttl.is_some_and(|ttl| age >= ttl) {
+            self.inner.remove(key);
+            self.stats.removal(RemovalCause::Expired);
+            return None;
+        }
+        if let Some((refresh_after, loader)) = &self.refresh {
+            if age >= *refresh_after {
+                self.spawn_refresh(key, loader.clone());
+            }
+        }
+        Some(loaded.value)
+    }
+
+    pub fn put(&self, key: K, value: V) {
+        let loaded_at = self.clock.monotonic();
+        self.inner.put(key, Loaded { value, loaded_at });
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let loaded = self.inner.remove(key)?;
+        self.stats.removal(RemovalCause::Explicit);
+        Some(loaded.value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// 未命中时用 `f` 生成并写入
+    pub fn get_or_insert_with(&self, key: K, f: impl FnOnce() -> V) -> V {
+        match self.get_or_load(key, || Ok::<_, ()>(f())) {
+            Ok(value) => value,
+            Err(()) => unreachable!(),
+        }
+    }
+
+    /// 未命中时用 `loader` 加载并写入，失败时返回错误且不写入
+    pub fn get_or_load<E>(&self, key: K, loader: impl FnOnce() -> Result<V, E>) -> Result<V, E> {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+        let flight = self.flights.acquire(&key);
+        let _guard = flight.lock.enter();
+        match self.lookup(&key) {
+            Some(value) => Ok(value),
+            None => {
+                let start = Instant::now();
+                let result = loader();
+                self.stats.load(result.is_ok(), start.elapsed());
+                result.inspect(|value| self.put(key.clone(), value.clone()))
+            }
+        }
+    }
+
+    /// `get_or_load` 的异步版本，等待加载时不阻塞线程
+    pub async fn get_or_load_async<E, F, Fut>(&self, key: K, loader: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+        let flight = self.flights.acquire(&key);
+        let _guard = flight.lock.enter_async().await;
+        match self.lookup(&key) {
+            Some(value) => Ok(value),
+            None => {
+                let start = Instant::now();
+                let result = loader().await;
+                self.stats.load(result.is_ok(), start.elapsed());
+                result.inspect(|value| self.put(key.clone(), value.clone()))
+            }
+        }
+    }
+
+    /// 每个 key 同时最多一个后台刷新，在 tokio 运行时内用阻塞线程池，否则新开线程
+    fn spawn_refresh(&self, key: &K, loader: Arc<RefreshFn<K, V>>) {
+        let mut refreshing = self
+            .refreshing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if !refreshing.insert(key.clone()) {
+            return;
+        }
+        drop(refreshing);
+        let refreshing = Refreshing {
+            keys: self.refreshing.clone(),
+            key: key.clone(),
+        };
+        let (inner, clock, stats) = (self.inner.clone(), self.clock.clone(), self.stats.clone());
+        let refresh = move || {
+            let key = &refreshing.key;
+            let start = Instant::now();
+            let result = loader(key);
+            stats.load(result.is_ok(), start.elapsed());
+            match result {
+                Ok(value) => {
+                    let loaded_at = clock.monotonic();
+                    inner.put(key.clone(), Loaded { value, loaded_at });
+                }
+                Err(e) => log::warn!("[Cache Refresh Error] {}", e),
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(refresh);
+            }
+            Err(_) => {
+                std::thread::spawn(refresh);
+            }
+        }
+    }
+}
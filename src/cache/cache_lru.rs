@@ -1,64 +1,115 @@
-use crate::cachekit::Cache;
-use std::collections::{BTreeMap, VecDeque};
+use crate::cache::cache_stats::StatsCounter;
+use crate::cachekit::{Cache, CacheStats, RemovalCause};
+use std::hash::Hash;
+use std::num::NonZeroUsize;
 
+/// 哈希表加双向链表的 LRU，`get`、`put`、`remove` 都是 O(1)。
+/// 容量为 0 时不缓存任何值。
 pub struct LruCache<K, V> {
-    map: BTreeMap<K, V>,
-    order: VecDeque<K>,
-    cap: usize,
+    inner: Option<lru::LruCache<K, V>>,
+    stats: StatsCounter,
 }
 
-impl<K: Ord + Clone + PartialEq, V> LruCache<K, V> {
+impl<K: Hash + Eq, V> LruCache<K, V> {
     pub fn new(capacity: usize) -> Self {
         Self {
-            map: BTreeMap::new(),
-            order: VecDeque::new(),
-            cap: capacity,
+            inner: NonZeroUsize::new(capacity).map(lru::LruCache::new),
+            stats: StatsCounter::default(),
         }
     }
-
-    fn update_order(&mut self, key: &K) {
-        if let Some(pos) = self.order.iter().position(|k| k == key) {
-            self.order.remove(pos);
-        }
-        self.order.push_back(key.clone());
-    }
 }
 
-impl<K: Ord + Clone + PartialEq + std::hash::Hash, V> Cache<K, V> for LruCache<K, V> {
+impl<K: Hash + Eq, V> Cache<K, V> for LruCache<K, V> {
     fn get(&mut self, key: &K) -> Option<&V> {
-        if self.map.contains_key(key) {
-            self.update_order(key);
-        }
-        self.map.get(key)
+        self.stats
+            .lookup(self.inner.as_mut().and_then(|inner| inner.get(key)))
     }
 
     fn put(&mut self, key: K, value: V) {
-        if self.map.contains_key(&key) {
-            self.map.insert(key.clone(), value);
-            self.update_order(&key);
-            return;
-        }
-
-        if self.map.len() >= self.cap {
-            if let Some(lru_key) = self.order.pop_front() {
-                self.map.remove(&lru_key);
+        if let Some(inner) = self.inner.as_mut() {
+            let cause = match inner.contains(&key) {
+                true => RemovalCause::Replaced,
+                false => RemovalCause::Capacity,
+            };
+            if inner.push(key, value).is_some() {
+                self.stats.removal(cause);
             }
         }
-
-        self.map.insert(key.clone(), value);
-        self.order.push_back(key);
     }
 
     fn remove(&mut self, key: &K) -> Option<V> {
-        self.order.retain(|k| k != key);
-        self.map.remove(key)
+        let value = self.inner.as_mut()?.pop(key)?;
+        self.stats.removal(RemovalCause::Explicit);
+        Some(value)
     }
 
     fn len(&self) -> usize {
-        self.map.len()
+        self.inner.as_ref().map_or(0, |inner| inner.len())
     }
 
     fn capacity(&self) -> usize {
-        self.cap
+        self.inner.as_ref().map_or(0, |inner| inner.cap().get())
+    }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.inner.as_ref()?.peek(key)
+    }
+
+    /// 从最近使用到最久未使用
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        match &self.inner {
+            Some(inner) => Box::new(inner.iter()),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn clear(&mut self) {
+        if let Some(inner) = self.inner.as_mut() {
+            self.stats.removals(RemovalCause::Explicit, inner.len());
+            inner.clear();
+        }
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &V) -> bool) {
+        let Some(inner) = self.inner.as_mut() else {
+            return;
+        };
+        // lru 没有 retain，从最久未使用一端依次弹出，保留的按原顺序放回
+        let mut kept = Vec::with_capacity(inner.len());
+        while let Some((key, value)) = inner.pop_lru() {
+            if f(&key, &value) {
+                kept.push((key, value));
+            } else {
+                self.stats.removal(RemovalCause::Explicit);
+            }
+        }
+        for (key, value) in kept {
+            inner.put(key, value);
+        }
+    }
+
+    /// 容量为 0 时清空并不再缓存
+    fn resize(&mut self, capacity: usize) {
+        let Some(capacity) = NonZeroUsize::new(capacity) else {
+            self.clear();
+            self.inner = None;
+            return;
+        };
+        match self.inner.as_mut() {
+            Some(inner) => {
+                let evicted = inner.len().saturating_sub(capacity.get());
+                inner.resize(capacity);
+                self.stats.removals(RemovalCause::Capacity, evicted);
+            }
+            None => self.inner = Some(lru::LruCache::new(capacity)),
+        }
+    }
+
+    fn record_stats(&mut self) {
+        self.stats.enable();
+    }
+
+    fn stats(&self) -> Option<CacheStats> {
+        self.stats.snapshot()
     }
 }
@@ -0,0 +1,152 @@
+use crate::cachekit::RemovalCause;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// 缓存统计快照
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// 过期移除的条目数
+    pub expired: u64,
+    /// 超出容量或总权重被淘汰的条目数
+    pub capacity_evicted: u64,
+    /// 调用 `remove`、`clear`、`retain` 删除的条目数
+    pub explicit_removed: u64,
+    /// 被新值覆盖的条目数
+    pub replaced: u64,
+    pub load_successes: u64,
+    pub load_failures: u64,
+    /// 所有加载（含失败）的总耗时
+    pub total_load_time: Duration,
+}
+
+impl CacheStats {
+    pub fn requests(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// 命中率，没有请求时为 1.0
+    pub fn hit_rate(&self) -> f64 {
+        match self.requests() {
+            0 => 1.0,
+            requests => self.hits as f64 / requests as f64,
+        }
+    }
+
+    pub fn miss_rate(&self) -> f64 {
+        1.0 - self.hit_rate()
+    }
+
+    /// 指定原因的移除数量
+    pub fn removals(&self, cause: RemovalCause) -> u64 {
+        match cause {
+            RemovalCause::Expired => self.expired,
+            RemovalCause::Capacity => self.capacity_evicted,
+            RemovalCause::Explicit => self.explicit_removed,
+            RemovalCause::Replaced => self.replaced,
+        }
+    }
+
+    pub fn loads(&self) -> u64 {
+        self.load_successes + self.load_failures
+    }
+
+    /// 平均加载耗时，没有加载时为 0
+    pub fn average_load_time(&self) -> Duration {
+        match self.loads() {
+            0 => Duration::ZERO,
+            loads => Duration::from_nanos((self.total_load_time.as_nanos() / loads as u128) as u64),
+        }
+    }
+
+    /// 累加另一个快照，用于汇总分片
+    pub(crate) fn merge(&mut self, other: &CacheStats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.expired += other.expired;
+        self.capacity_evicted += other.capacity_evicted;
+        self.explicit_removed += other.explicit_removed;
+        self.replaced += other.replaced;
+        self.load_successes += other.load_successes;
+        self.load_failures += other.load_failures;
+        self.total_load_time += other.total_load_time;
+    }
+}
+
+/// 缓存内部的计数器，默认关闭，关闭时记录操作什么都不做
+#[derive(Default)]
+pub(crate) struct StatsCounter {
+    enabled: AtomicBool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    removals: [AtomicU64; 4],
+    load_successes: AtomicU64,
+    load_failures: AtomicU64,
+    load_nanos: AtomicU64,
+}
+
+impl StatsCounter {
+    pub(crate) fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    fn add(&self, counter: &AtomicU64, n: u64) {
+        if self.enabled.load(Ordering::Relaxed) {
+            counter.fetch_add(n, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn hit(&self) {
+        self.add(&self.hits, 1);
+    }
+
+    pub(crate) fn miss(&self) {
+        self.add(&self.misses, 1);
+    }
+
+    /// 按结果记录一次命中或未命中
+    pub(crate) fn lookup<T>(&self, found: Option<T>) -> Option<T> {
+        match found {
+            Some(_) => self.hit(),
+            None => self.miss(),
+        }
+        found
+    }
+
+    pub(crate) fn removal(&self, cause: RemovalCause) {
+        self.removals(cause, 1);
+    }
+
+    pub(crate) fn removals(&self, cause: RemovalCause, n: usize) {
+        self.add(&self.removals[cause as usize], n as u64);
+    }
+
+    pub(crate) fn load(&self, success: bool, elapsed: Duration) {
+        let counter = match success {
+            true => &self.load_successes,
+            false => &self.load_failures,
+        };
+        self.add(counter, 1);
+        self.add(&self.load_nanos, elapsed.as_nanos() as u64);
+    }
+
+    /// 没有开启时返回 `None`
+    pub(crate) fn snapshot(&self) -> Option<CacheStats> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+        let removal = |cause: RemovalCause| self.removals[cause as usize].load(Ordering::Relaxed);
+        Some(CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            expired: removal(RemovalCause::Expired),
+            capacity_evicted: removal(RemovalCause::Capacity),
+            explicit_removed: removal(RemovalCause::Explicit),
+            replaced: removal(RemovalCause::Replaced),
+            load_successes: self.load_successes.load(Ordering::Relaxed),
+            load_failures: self.load_failures.load(Ordering::Relaxed),
+            total_load_time: Duration::from_nanos(self.load_nanos.load(Ordering::Relaxed)),
+        })
+    }
+}
@@ -1,60 +1,260 @@
-use std::collections::HashMap;
-use std::time::{Duration, Instant};
+use crate::cache::cache_stats::StatsCounter;
+use crate::cachekit::{Cache, CacheStats, EvictionListener, ExpiringCache, RemovalCause};
+use crate::clockkit::{self, Clock};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
 
-/// 结构体：包含值和插入时间
+/// `order` 较短时不压缩，避免条目很少时频繁重建
+const MIN_COMPACT: usize = 16;
+
+/// 结构体：包含值和插入时间（时钟的单调时间，不受系统时间调整影响）
 struct CacheEntry<V> {
     value: V,
-    inserted: Instant,
+    inserted: Duration,
 }
 
 pub struct TimedCache<K, V> {
     map: HashMap<K, CacheEntry<V>>,
+    /// 按写入先后排列的 key 和写入时间，清理时从队头开始，
+    /// 时间和 map 中不一致的是被覆盖或删除后留下的旧记录，旧记录多于有效记录时压缩
+    order: VecDeque<(K, Duration)>,
     timeout: Duration,
+    /// `resize` 设置的条目数上限，超出时淘汰最早写入的
+    max_entries: Option<usize>,
+    clock: Arc<dyn Clock>,
+    listener: Option<Arc<EvictionListener<K, V>>>,
+    stats: StatsCounter,
 }
 
 impl<K: std::cmp::Eq + std::hash::Hash + Clone, V> TimedCache<K, V> {
     pub fn new(timeout: Duration) -> Self {
+        Self::with_clock(timeout, clockkit::system())
+    }
+
+    /// 使用指定时钟判断过期
+    pub fn with_clock(timeout: Duration, clock: Arc<dyn Clock>) -> Self {
         Self {
             map: HashMap::new(),
+            order: VecDeque::new(),
             timeout,
+            max_entries: None,
+            clock,
+            listener: None,
+            stats: StatsCounter::default(),
         }
     }
 
+    /// 设置移除回调，过期、删除、覆盖时都会调用
+    pub fn set_eviction_listener(
+        &mut self,
+        listener: impl Fn(&K, &V, RemovalCause) + Send + Sync + 'static,
+    ) {
+        self.listener = Some(Arc::new(listener));
+    }
+
+    /// 开启统计
+    pub fn record_stats(&mut self) {
+        self.stats.enable();
+    }
+
+    /// 统计快照，没有开启统计时为 `None`
+    pub fn stats(&self) -> Option<CacheStats> {
+        self.stats.snapshot()
+    }
+
+    /// 写入时顺带清理已过期的条目
     pub fn put(&mut self, key: K, value: V) {
-        let entry = CacheEntry {
-            value,
-            inserted: Instant::now(),
-        };
-        self.map.insert(key, entry);
+        self.purge_expired();
+        let inserted = self.clock.monotonic();
+        let entry = CacheEntry { value, inserted };
+        // 同一时刻覆盖时已有的记录仍然有效，不再重复记录
+        if self
+            .map
+            .get(&key)
+            .is_none_or(|old| old.inserted != inserted)
+        {
+            self.order.push_back((key.clone(), inserted));
+        }
+        if let Some(old) = self.map.insert(key.clone(), entry) {
+            self.notify(&key, &old.value, RemovalCause::Replaced);
+        }
+        self.evict_to_capacity();
+        self.compact_order();
     }
 
     pub fn get(&mut self, key: &K) -> Option<&V> {
         let expired = {
             if let Some(entry) = self.map.get(key) {
-                entry.inserted.elapsed() >= self.timeout
+                self.is_expired(entry)
             } else {
+                self.stats.miss();
                 return None;
             }
         };
 
         if expired {
-            self.map.remove(key);
+            if let Some(entry) = self.map.remove(key) {
+                self.notify(key, &entry.value, RemovalCause::Expired);
+            }
+            self.stats.miss();
             None
         } else {
             // 安全再次访问
+            self.stats.hit();
             self.map.get(key).map(|entry| &entry.value)
         }
     }
 
     pub fn remove(&mut self, key: &K) -> Option<V> {
-        self.map.remove(key).map(|e| e.value)
+        let entry = self.map.remove(key)?;
+        self.notify(key, &entry.value, RemovalCause::Explicit);
+        self.compact_order();
+        Some(entry.value)
     }
 
     pub fn len(&self) -> usize {
         self.map.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// 调用过 `resize` 时为设置的上限，否则为哈希表当前容量
     pub fn capacity(&self) -> usize {
-        self.map.capacity()
+        self.max_entries.unwrap_or_else(|| self.map.capacity())
+    }
+
+    /// 先清理过期条目，再按写入先后淘汰到不超过 `max_entries`
+    fn evict_to_capacity(&mut self) {
+        let Some(max) = self.max_entries else {
+            return;
+        };
+        self.purge_expired();
+        while self.map.len() > max {
+            let Some((key, inserted)) = self.order.pop_front() else {
+                break;
+            };
+            if self.map.get(&key).is_some_and(|e| e.inserted == inserted) {
+                if let Some(entry) = self.map.remove(&key) {
+                    self.notify(&key, &entry.value, RemovalCause::Capacity);
+                }
+            }
+        }
+    }
+
+    /// 旧记录多于有效记录时只保留有效记录，压缩的开销均摊到每次写入和删除
+    fn compact_order(&mut self) {
+        if self.order.len() <= (self.map.len() * 2).max(MIN_COMPACT) {
+            return;
+        }
+        let map = &self.map;
+        self.order
+            .retain(|(key, inserted)| map.get(key).is_some_and(|e| e.inserted == *inserted));
+    }
+
+    fn is_expired(&self, entry: &CacheEntry<V>) -> bool {
+        self.clock.monotonic().saturating_sub(entry.inserted) >= self.timeout
+    }
+
+    fn notify(&self, key: &K, value: &V, cause: RemovalCause) {
+        self.stats.removal(cause);
+        if let Some(listener) = &self.listener {
+            listener(key, value, cause);
+        }
+    }
+}
+
+impl<K: std::cmp::Eq + std::hash::Hash + Clone, V> ExpiringCache for TimedCache<K, V> {
+    /// 所有条目的过期时长相同，按写入顺序只检查到第一个未过期的记录
+    fn purge_expired(&mut self) -> usize {
+        let mut purged = 0;
+        let now = self.clock.monotonic();
+        while let Some((key, inserted)) = self.order.front() {
+            if now.saturating_sub(*inserted) < self.timeout {
+                break;
+            }
+            let (key, inserted) = (key.clone(), *inserted);
+            self.order.pop_front();
+            if self.map.get(&key).is_some_and(|e| e.inserted == inserted) {
+                if let Some(entry) = self.map.remove(&key) {
+                    self.notify(&key, &entry.value, RemovalCause::Expired);
+                    purged += 1;
+                }
+            }
+        }
+        purged
+    }
+}
+
+impl<K: std::cmp::Eq + std::hash::Hash + Clone, V> Cache<K, V> for TimedCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        TimedCache::get(self, key)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        TimedCache::put(self, key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        TimedCache::remove(self, key)
+    }
+
+    fn len(&self) -> usize {
+        TimedCache::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        TimedCache::capacity(self)
+    }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.map
+            .get(key)
+            .filter(|entry| !self.is_expired(entry))
+            .map(|entry| &entry.value)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        Box::new(
+            self.map
+                .iter()
+                .filter(|(_, entry)| !self.is_expired(entry))
+                .map(|(key, entry)| (key, &entry.value)),
+        )
+    }
+
+    fn clear(&mut self) {
+        for (key, entry) in std::mem::take(&mut self.map) {
+            self.notify(&key, &entry.value, RemovalCause::Explicit);
+        }
+        self.order.clear();
+    }
+
+    /// 删除的条目以 `Explicit` 通知，留在 `order` 里的旧记录由后续清理跳过或压缩
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &V) -> bool) {
+        let keys: Vec<K> = self
+            .map
+            .iter()
+            .filter(|(key, entry)| !f(key, &entry.value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            TimedCache::remove(self, &key);
+        }
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        self.max_entries = Some(capacity);
+        self.evict_to_capacity();
+    }
+
+    fn record_stats(&mut self) {
+        TimedCache::record_stats(self)
+    }
+
+    fn stats(&self) -> Option<CacheStats> {
+        TimedCache::stats(self)
     }
 }
@@ -0,0 +1,263 @@
+use crate::cache::cache_stats::StatsCounter;
+use crate::cachekit::{Cache, CacheStats, RemovalCause};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+/// 4 行的 count-min sketch，计数上限 15，
+/// 累计次数达到采样数后全部减半，让旧的热点逐渐冷却
+struct CountMinSketch {
+    rows: [Vec<u8>; 4],
+    mask: usize,
+    additions: usize,
+    sample_size: usize,
+    hasher: RandomState,
+}
+
+impl CountMinSketch {
+    fn new(capacity: usize) -> Self {
+        let width = capacity.max(16).next_power_of_two();
+        Self {
+            rows: std::array::from_fn(|_| vec![0; width]),
+            mask: width - 1,
+            additions: 0,
+            sample_size: capacity.max(16) * 10,
+            hasher: RandomState::new(),
+        }
+    }
+
+    fn indexes<K: Hash>(&self, key: &K) -> [usize; 4] {
+        let hash = self.hasher.hash_one(key);
+        let (h1, h2) = (hash as usize, (hash >> 32) as usize | 1);
+        std::array::from_fn(|i| h1.wrapping_add(i.wrapping_mul(h2)) & self.mask)
+    }
+
+    fn frequency<K: Hash>(&self, key: &K) -> u8 {
+        let indexes = self.indexes(key);
+        (0..4).map(|i| self.rows[i][indexes[i]]).min().unwrap()
+    }
+
+    fn increment<K: Hash>(&mut self, key: &K) {
+        let indexes = self.indexes(key);
+        for (row, index) in self.rows.iter_mut().zip(indexes) {
+            row[index] = (row[index] + 1).min(15);
+        }
+        self.additions += 1;
+        if self.additions >= self.sample_size {
+            for row in &mut self.rows {
+                row.iter_mut().for_each(|count| *count /= 2);
+            }
+            self.additions /= 2;
+        }
+    }
+}
+
+/// W-TinyLFU（Caffeine 的淘汰策略）：
+/// 新 key 先进入占 1% 容量的窗口 LRU，被窗口淘汰后要和主区的淘汰候选比较访问频率，
+/// 频率更高才能进入主区。主区是分段 LRU，试用区的 key 再次访问后升入保护区（80%）。
+/// 频率由 count-min sketch 估算，扫描式的一次性访问因此很难挤掉热点。
+pub struct TinyLfuCache<K, V> {
+    window: lru::LruCache<K, V>,
+    probation: lru::LruCache<K, V>,
+    protected: lru::LruCache<K, V>,
+    sketch: CountMinSketch,
+    window_cap: usize,
+    protected_cap: usize,
+    cap: usize,
+    stats: StatsCounter,
+}
+
+impl<K: Hash + Eq + Clone, V> TinyLfuCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        let (window_cap, protected_cap) = Self::split(capacity);
+        Self {
+            window: lru::LruCache::unbounded(),
+            probation: lru::LruCache::unbounded(),
+            protected: lru::LruCache::unbounded(),
+            sketch: CountMinSketch::new(capacity),
+            window_cap,
+            protected_cap,
+            cap: capacity,
+            stats: StatsCounter::default(),
+        }
+    }
+
+    /// 窗口占 1%，保护区占主区的 80%
+    fn split(capacity: usize) -> (usize, usize) {
+        let window_cap = (capacity / 100).max(1).min(capacity);
+        let main_cap = capacity - window_cap;
+        (window_cap, main_cap * 8 / 10)
+    }
+
+    fn main_len(&self) -> usize {
+        self.probation.len() + self.protected.len()
+    }
+
+    /// 试用区的 key 升入保护区，保护区超出时把最久未用的降回试用区
+    fn promote(&mut self, key: &K) {
+        if let Some(value) = self.probation.pop(key) {
+            self.protected.put(key.clone(), value);
+            if self.protected.len() > self.protected_cap {
+                if let Some((key, value)) = self.protected.pop_lru() {
+                    self.probation.put(key, value);
+                }
+            }
+        }
+    }
+
+    /// 窗口淘汰的 key 尝试进入主区，落选的一方记为容量淘汰
+    fn admit(&mut self, key: K, value: V) {
+        if self.main_len() < self.cap - self.window_cap {
+            self.probation.put(key, value);
+            return;
+        }
+        let victim = match self.probation.peek_lru() {
+            Some((victim, _)) => victim,
+            None => match self.protected.peek_lru() {
+                Some((victim, _)) => victim,
+                None => return,
+            },
+        };
+        self.stats.removal(RemovalCause::Capacity);
+        if self.sketch.frequency(&key) <= self.sketch.frequency(victim) {
+            return;
+        }
+        if self.probation.pop_lru().is_none() {
+            self.protected.pop_lru();
+        }
+        self.probation.put(key, value);
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for TinyLfuCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.sketch.increment(key);
+        if self.window.contains(key) {
+            return self.stats.lookup(self.window.get(key));
+        }
+        self.promote(key);
+        let value = self.protected.get(key).or_else(|| self.probation.get(key));
+        self.stats.lookup(value)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        if self.cap == 0 {
+            return;
+        }
+        self.sketch.increment(&key);
+        if self.window.contains(&key) {
+            self.window.put(key, value);
+            self.stats.removal(RemovalCause::Replaced);
+            return;
+        }
+        if self.probation.contains(&key) || self.protected.contains(&key) {
+            self.promote(&key);
+            let slot = match self.protected.get_mut(&key) {
+                Some(slot) => Some(slot),
+                None => self.probation.get_mut(&key),
+            };
+            if let Some(slot) = slot {
+                *slot = value;
+            }
+            self.stats.removal(RemovalCause::Replaced);
+            return;
+        }
+
+        self.window.put(key, value);
+        if self.window.len() > self.window_cap {
+            if let Some((key, value)) = self.window.pop_lru() {
+                self.admit(key, value);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let value = self
+            .window
+            .pop(key)
+            .or_else(|| self.probation.pop(key))
+            .or_else(|| self.protected.pop(key))?;
+        self.stats.removal(RemovalCause::Explicit);
+        Some(value)
+    }
+
+    fn len(&self) -> usize {
+        self.window.len() + self.main_len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        self.window
+            .peek(key)
+            .or_else(|| self.probation.peek(key))
+            .or_else(|| self.protected.peek(key))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        Box::new(
+            self.window
+                .iter()
+                .chain(self.probation.iter())
+                .chain(self.protected.iter()),
+        )
+    }
+
+    /// 访问频率的统计一并清空
+    fn clear(&mut self) {
+        self.stats.removals(RemovalCause::Explicit, self.len());
+        self.window.clear();
+        self.probation.clear();
+        self.protected.clear();
+        self.sketch = CountMinSketch::new(self.cap);
+    }
+
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &V) -> bool) {
+        let keys: Vec<K> = self
+            .iter()
+            .filter(|(key, value)| !f(key, value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            Cache::remove(self, &key);
+        }
+    }
+
+    /// 缩小时依次从试用区、保护区、窗口的最久未使用端淘汰，再按新的比例调整各区
+    fn resize(&mut self, capacity: usize) {
+        let (window_cap, protected_cap) = Self::split(capacity);
+        self.cap = capacity;
+        self.window_cap = window_cap;
+        self.protected_cap = protected_cap;
+        while self.len() > self.cap {
+            let evicted = self
+                .probation
+                .pop_lru()
+                .or_else(|| self.protected.pop_lru())
+                .or_else(|| self.window.pop_lru());
+            if evicted.is_none() {
+                break;
+            }
+            self.stats.removal(RemovalCause::Capacity);
+        }
+        while self.window.len() > self.window_cap {
+            if let Some((key, value)) = self.window.pop_lru() {
+                self.probation.put(key, value);
+            }
+        }
+        while self.protected.len() > self.protected_cap {
+            if let Some((key, value)) = self.protected.pop_lru() {
+                self.probation.put(key, value);
+            }
+        }
+    }
+
+    fn record_stats(&mut self) {
+        self.stats.enable();
+    }
+
+    fn stats(&self) -> Option<CacheStats> {
+        self.stats.snapshot()
+    }
+}
@@ -1,3 +1,11 @@
+pub(crate) mod cache_arc;
+pub(crate) mod cache_builder;
+pub(crate) mod cache_concurrent;
+pub(crate) mod cache_disk;
 pub(crate) mod cache_fifo;
+pub(crate) mod cache_lfu;
+pub(crate) mod cache_loading;
 pub(crate) mod cache_lru;
+pub(crate) mod cache_stats;
 pub(crate) mod cache_time;
+pub(crate) mod cache_tinylfu;
@@ -1,15 +1,129 @@
+use crate::cache::cache_arc::ArcCache;
+use crate::cache::cache_concurrent::ShardedCache;
 use crate::cache::cache_fifo::FifoCache;
+use crate::cache::cache_lfu::LfuCache;
 use crate::cache::cache_lru::LruCache;
-use crate::cache::cache_time::TimedCache;
+use crate::cache::cache_tinylfu::TinyLfuCache;
+use crate::clockkit::Clock;
 use std::boxed::Box;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::task::JoinHandle;
+
+pub use crate::cache::cache_builder::{CacheBuilder, ComposedCache, Weigher};
+pub use crate::cache::cache_disk::{DiskCache, TieredCache};
+pub use crate::cache::cache_loading::{LoadingCache, RefreshFn};
+pub use crate::cache::cache_stats::CacheStats;
+pub use crate::cache::cache_time::TimedCache;
 
 pub trait Cache<K, V> {
     fn get(&mut self, key: &K) -> Option<&V>;
     fn put(&mut self, key: K, value: V);
     fn remove(&mut self, key: &K) -> Option<V>;
     fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
     fn capacity(&self) -> usize;
+    /// 读取但不影响淘汰顺序和统计
+    fn peek(&self, key: &K) -> Option<&V>;
+    fn contains_key(&self, key: &K) -> bool {
+        self.peek(key).is_some()
+    }
+    /// 遍历所有未过期的条目，顺序由具体实现决定
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_>;
+    fn keys<'a>(&'a self) -> Box<dyn Iterator<Item = &'a K> + 'a>
+    where
+        V: 'a,
+    {
+        Box::new(self.iter().map(|(key, _)| key))
+    }
+    /// 删除所有条目
+    fn clear(&mut self);
+    /// 只保留 `f` 返回 `true` 的条目
+    fn retain(&mut self, f: &mut dyn FnMut(&K, &V) -> bool);
+    fn put_all(&mut self, entries: Vec<(K, V)>) {
+        for (key, value) in entries {
+            self.put(key, value);
+        }
+    }
+    /// 按 `keys` 的顺序返回值的克隆，和逐个 `get` 一样影响淘汰顺序
+    fn get_all(&mut self, keys: &[K]) -> Vec<Option<V>>
+    where
+        V: Clone,
+    {
+        keys.iter().map(|key| self.get(key).cloned()).collect()
+    }
+    /// 修改容量，超出新容量的条目按淘汰策略移除
+    fn resize(&mut self, capacity: usize);
+    /// 开启统计，默认不统计；不支持统计的实现忽略此调用
+    fn record_stats(&mut self) {}
+    /// 统计快照，没有开启统计时为 `None`
+    fn stats(&self) -> Option<CacheStats> {
+        None
+    }
+}
+
+/// 条目被移除的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalCause {
+    /// 过期
+    Expired,
+    /// 超出容量或总权重被淘汰
+    Capacity,
+    /// 调用 `remove` 删除
+    Explicit,
+    /// 同一个 key 写入了新值
+    Replaced,
+}
+
+/// 移除回调，参数为 key、value 和移除原因
+pub type EvictionListener<K, V> = dyn Fn(&K, &V, RemovalCause) + Send + Sync;
+
+/// 支持主动清理过期条目的缓存
+pub trait ExpiringCache {
+    /// 移除所有已过期的条目，返回移除数量
+    fn purge_expired(&mut self) -> usize;
+}
+
+/// 在 tokio 上每隔 `interval` 清理一次过期条目，缓存被释放后自动停止
+pub fn spawn_sweeper<C>(cache: &Arc<Mutex<C>>, interval: Duration) -> JoinHandle<()>
+where
+    C: ExpiringCache + Send + 'static,
+{
+    let cache = Arc::downgrade(cache);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            match cache.upgrade() {
+                Some(cache) => {
+                    cache.lock().unwrap().purge_expired();
+                }
+                None => break,
+            }
+        }
+    })
+}
+
+/// 线程安全的缓存，方法都是 `&self`，可以放进 `Arc` 在线程间共享。
+/// `get` 返回值的克隆，大对象可以存 `Arc<V>`。
+pub trait ConcurrentCache<K, V>: Send + Sync {
+    fn get(&self, key: &K) -> Option<V>;
+    fn put(&self, key: K, value: V);
+    fn remove(&self, key: &K) -> Option<V>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn capacity(&self) -> usize;
+    /// 开启统计，默认不统计；不支持统计的实现忽略此调用
+    fn record_stats(&self) {}
+    /// 统计快照，没有开启统计时为 `None`
+    fn stats(&self) -> Option<CacheStats> {
+        None
+    }
 }
 
 pub fn fifo_cache<K, V>(capacity: usize) -> Box<dyn Cache<K, V>>
@@ -22,12 +136,39 @@ where
 
 pub fn lru_cache<K, V>(capacity: usize) -> Box<dyn Cache<K, V>>
 where
-    K: 'static + Clone + std::hash::Hash + Ord + PartialEq,
+    K: 'static + std::hash::Hash + Eq,
     V: 'static,
 {
     Box::new(LruCache::new(capacity))
 }
 
+/// LFU 缓存，淘汰访问次数最少的 key
+pub fn lfu_cache<K, V>(capacity: usize) -> Box<dyn Cache<K, V>>
+where
+    K: 'static + Clone + std::hash::Hash + Eq,
+    V: 'static,
+{
+    Box::new(LfuCache::new(capacity))
+}
+
+/// ARC 缓存，在最近访问和访问频率之间自适应
+pub fn arc_cache<K, V>(capacity: usize) -> Box<dyn Cache<K, V>>
+where
+    K: 'static + Clone + std::hash::Hash + Eq,
+    V: 'static,
+{
+    Box::new(ArcCache::new(capacity))
+}
+
+/// W-TinyLFU 缓存，用频率估算决定新 key 能否挤掉旧 key，抗扫描
+pub fn tinylfu_cache<K, V>(capacity: usize) -> Box<dyn Cache<K, V>>
+where
+    K: 'static + Clone + std::hash::Hash + Eq,
+    V: 'static,
+{
+    Box::new(TinyLfuCache::new(capacity))
+}
+
 pub fn time_cache<K, V>(timeout_secs: u64) -> TimedCache<K, V>
 where
     K: Eq + std::hash::Hash + Clone,
@@ -35,3 +176,162 @@ where
 {
     TimedCache::new(Duration::from_secs(timeout_secs))
 }
+
+/// 使用指定时钟的过期缓存，测试时可传入 `MockClock`
+pub fn time_cache_with_clock<K, V>(timeout_secs: u64, clock: Arc<dyn Clock>) -> TimedCache<K, V>
+where
+    K: Eq + std::hash::Hash + Clone,
+    V: 'static,
+{
+    TimedCache::with_clock(Duration::from_secs(timeout_secs), clock)
+}
+
+/// 线程安全的 FIFO 缓存，按 key 分片加锁
+pub fn concurrent_fifo_cache<K, V>(capacity: usize) -> Arc<dyn ConcurrentCache<K, V>>
+where
+    K: 'static + Clone + std::hash::Hash + Eq + Send,
+    V: 'static + Clone + Send,
+{
+    let shards = ShardedCache::<FifoCache<K, V>>::default_shards(capacity);
+    Arc::new(ShardedCache::new(shards, |i| {
+        FifoCache::new(ShardedCache::<FifoCache<K, V>>::shard_capacity(
+            capacity, shards, i,
+        ))
+    }))
+}
+
+/// 线程安全的 LRU 缓存，按 key 分片加锁
+pub fn concurrent_lru_cache<K, V>(capacity: usize) -> Arc<dyn ConcurrentCache<K, V>>
+where
+    K: 'static + std::hash::Hash + Eq + Send,
+    V: 'static + Clone + Send,
+{
+    let shards = ShardedCache::<LruCache<K, V>>::default_shards(capacity);
+    Arc::new(ShardedCache::new(shards, |i| {
+        LruCache::new(ShardedCache::<LruCache<K, V>>::shard_capacity(
+            capacity, shards, i,
+        ))
+    }))
+}
+
+/// 线程安全的过期缓存，按 key 分片加锁
+pub fn concurrent_time_cache<K, V>(timeout_secs: u64) -> Arc<dyn ConcurrentCache<K, V>>
+where
+    K: 'static + Clone + std::hash::Hash + Eq + Send,
+    V: 'static + Clone + Send,
+{
+    concurrent_time_cache_with_clock(timeout_secs, crate::clockkit::system())
+}
+
+/// 使用指定时钟的线程安全过期缓存
+pub fn concurrent_time_cache_with_clock<K, V>(
+    timeout_secs: u64,
+    clock: Arc<dyn Clock>,
+) -> Arc<dyn ConcurrentCache<K, V>>
+where
+    K: 'static + Clone + std::hash::Hash + Eq + Send,
+    V: 'static + Clone + Send,
+{
+    let shards = ShardedCache::<TimedCache<K, V>>::default_shards(usize::MAX);
+    Arc::new(ShardedCache::new(shards, |_| {
+        TimedCache::with_clock(Duration::from_secs(timeout_secs), clock.clone())
+    }))
+}
+
+/// 用缓存包装纯函数，参数的元组作为 key，同一组参数并发调用时函数体只执行一次。
+/// 缓存方式写在函数前：`lru(容量)`、`time(秒)` 或 `lru(容量), time(秒)`；
+/// 只写 `time(秒)` 时不限容量，写入时清理已过期的条目。
+/// 支持 `async fn`；返回值字面写作 `Result<T, E>` 时只缓存 `Ok`，
+/// `io::Result<T>` 这类别名按普通返回值处理，`Err` 也会被缓存。
+/// 参数和返回值都需要是 `'static + Clone + Send + Sync`，参数还需要 `Hash + Eq`。
+/// ```
+/// use rovkit::memoize;
+///
+/// memoize! {
+///     lru(100);
+///     /// 斐波那契
+///     pub fn fib(n: u64) -> u64 {
+///         if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+///     }
+/// }
+///
+/// memoize! {
+///     lru(1000), time(60);
+///     fn parse(s: String) -> Result<i64, String> {
+///         s.parse().map_err(|e| format!("{}", e))
+///     }
+/// }
+///
+/// memoize! {
+///     time(60);
+///     fn double(n: u32) -> u32 { n * 2 }
+/// }
+///
+/// assert_eq!(fib(80), 23416728348467685);
+/// assert_eq!(parse("42".to_string()), Ok(42));
+/// assert_eq!(double(21), 42);
+/// ```
+#[macro_export]
+macro_rules! memoize {
+    (lru($cap:expr), time($secs:expr); $($item:tt)*) => {
+        $crate::memoize!(@cache [$crate::cachekit::LoadingCache::new($cap)
+            .time_to_live(std::time::Duration::from_secs($secs))] $($item)*);
+    };
+    (lru($cap:expr); $($item:tt)*) => {
+        $crate::memoize!(@cache [$crate::cachekit::LoadingCache::new($cap)] $($item)*);
+    };
+    (time($secs:expr); $($item:tt)*) => {
+        $crate::memoize!(@cache [$crate::cachekit::LoadingCache::timed($secs)] $($item)*);
+    };
+
+    (@cache [$cache:expr] $(#[$meta:meta])* $vis:vis async fn $name:ident($($arg:ident: $ty:ty),* $(,)?)
+        -> Result<$ok:ty, $err:ty> $body:block) => {
+        $(#[$meta])*
+        $vis async fn $name($($arg: $ty),*) -> Result<$ok, $err> {
+            static CACHE: std::sync::OnceLock<$crate::cachekit::LoadingCache<($($ty,)*), $ok>> =
+                std::sync::OnceLock::new();
+            let cache = CACHE.get_or_init(|| $cache);
+            cache
+                .get_or_load_async(($($arg.clone(),)*), move || async move $body)
+                .await
+        }
+    };
+    (@cache [$cache:expr] $(#[$meta:meta])* $vis:vis async fn $name:ident($($arg:ident: $ty:ty),* $(,)?)
+        -> $ret:ty $body:block) => {
+        $(#[$meta])*
+        $vis async fn $name($($arg: $ty),*) -> $ret {
+            static CACHE: std::sync::OnceLock<$crate::cachekit::LoadingCache<($($ty,)*), $ret>> =
+                std::sync::OnceLock::new();
+            let cache = CACHE.get_or_init(|| $cache);
+            let loaded = cache
+                .get_or_load_async(($($arg.clone(),)*), move || async move {
+                    Ok::<_, std::convert::Infallible>(async move $body.await)
+                })
+                .await;
+            match loaded {
+                Ok(value) => value,
+                Err(never) => match never {},
+            }
+        }
+    };
+    (@cache [$cache:expr] $(#[$meta:meta])* $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?)
+        -> Result<$ok:ty, $err:ty> $body:block) => {
+        $(#[$meta])*
+        $vis fn $name($($arg: $ty),*) -> Result<$ok, $err> {
+            static CACHE: std::sync::OnceLock<$crate::cachekit::LoadingCache<($($ty,)*), $ok>> =
+                std::sync::OnceLock::new();
+            let cache = CACHE.get_or_init(|| $cache);
+            cache.get_or_load(($($arg.clone(),)*), move || $body)
+        }
+    };
+    (@cache [$cache:expr] $(#[$meta:meta])* $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?)
+        -> $ret:ty $body:block) => {
+        $(#[$meta])*
+        $vis fn $name($($arg: $ty),*) -> $ret {
+            static CACHE: std::sync::OnceLock<$crate::cachekit::LoadingCache<($($ty,)*), $ret>> =
+                std::sync::OnceLock::new();
+            let cache = CACHE.get_or_init(|| $cache);
+            cache.get_or_insert_with(($($arg.clone(),)*), move || $body)
+        }
+    };
+}
@@ -0,0 +1,179 @@
+//!
+//! 可注入的时钟，调度、缓存过期等逻辑通过 `Clock` 取时间，测试时可替换为 `MockClock`
+//!
+
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+/// 等待到期的 Future
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// 时钟
+pub trait Clock: Send + Sync {
+    /// 当前时间
+    fn now(&self) -> DateTime<Utc>;
+
+    /// 等待到 `deadline`，已过期则立即返回
+    fn sleep_until(&self, deadline: DateTime<Utc>) -> Sleep;
+
+    /// 单调时间：自固定起点经过的时长，不受系统时间调整影响，用于计算间隔和过期
+    fn monotonic(&self) -> Duration;
+
+    /// 等待 `duration`
+    fn sleep(&self, duration: Duration) -> Sleep {
+        let duration = chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX);
+        let deadline = self.now().checked_add_signed(duration);
+        self.sleep_until(deadline.unwrap_or(DateTime::<Utc>::MAX_UTC))
+    }
+}
+
+/// 系统时钟，等待基于 tokio 定时器
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn monotonic(&self) -> Duration {
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        ORIGIN.get_or_init(Instant::now).elapsed()
+    }
+
+    fn sleep_until(&self, deadline: DateTime<Utc>) -> Sleep {
+        let delay = (deadline - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+        Box::pin(tokio::time::sleep(delay))
+    }
+
+    /// 基于单调时间等待，系统时间调整不影响
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// 默认的系统时钟
+pub fn system() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+struct MockState {
+    origin: DateTime<Utc>,
+    now: DateTime<Utc>,
+    sleepers: Vec<(DateTime<Utc>, oneshot::Sender<()>)>,
+}
+
+/// 手动推进的虚拟时钟，时间只在调用 `advance`、`set` 或 `run_for` 时变化
+pub struct MockClock {
+    state: Mutex<MockState>,
+}
+
+impl MockClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            state: Mutex::new(MockState {
+                origin: start,
+                now: start,
+                sleepers: Vec::new(),
+            }),
+        }
+    }
+
+    /// 设置当前时间并唤醒到期的等待，不允许回拨
+    pub fn set(&self, now: DateTime<Utc>) {
+        let mut state = self.state.lock().unwrap();
+        if now < state.now {
+            return;
+        }
+        state.now = now;
+        let (due, pending) = state
+            .sleepers
+            .drain(..)
+            .partition::<Vec<_>, _>(|(deadline, _)| *deadline <= now);
+        state.sleepers = pending;
+        for (_, waker) in due {
+            let _ = waker.send(());
+        }
+    }
+
+    /// 推进时间，中间的到期点被一次性跳过
+    pub fn advance(&self, duration: Duration) {
+        let now = self.now() + chrono::Duration::from_std(duration).unwrap();
+        self.set(now);
+    }
+
+    /// 按到期顺序逐个推进 `duration`，每次唤醒后让出调度，
+    /// 使被唤醒的任务（如当前线程运行时上的异步任务）运行到下一次等待
+    pub async fn run_for(&self, duration: Duration) {
+        let target = self.now() + chrono::Duration::from_std(duration).unwrap();
+        loop {
+            Self::settle().await;
+            let next = {
+                let mut state = self.state.lock().unwrap();
+                state.sleepers.retain(|(_, waker)| !waker.is_closed());
+                state
+                    .sleepers
+                    .iter()
+                    .map(|(deadline, _)| *deadline)
+                    .filter(|deadline| *deadline <= target)
+                    .min()
+            };
+            match next {
+                Some(next) => self.set(next),
+                None => break,
+            }
+        }
+        self.set(target);
+        Self::settle().await;
+    }
+
+    /// 等待中的 sleep 数量
+    pub fn sleepers(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.sleepers.retain(|(_, waker)| !waker.is_closed());
+        state.sleepers.len()
+    }
+
+    async fn settle() {
+        for _ in 0..32 {
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new(Utc::now())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.state.lock().unwrap().now
+    }
+
+    /// 虚拟时间不会回拨，`monotonic` 就是相对起始时间经过的时长
+    fn monotonic(&self) -> Duration {
+        let state = self.state.lock().unwrap();
+        (state.now - state.origin)
+            .to_std()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    fn sleep_until(&self, deadline: DateTime<Utc>) -> Sleep {
+        let mut state = self.state.lock().unwrap();
+        if deadline <= state.now {
+            return Box::pin(std::future::ready(()));
+        }
+        let (waker, wait) = oneshot::channel();
+        state.sleepers.push((deadline, waker));
+        Box::pin(async move {
+            let _ = wait.await;
+        })
+    }
+}
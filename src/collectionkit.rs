@@ -1,7 +1,6 @@
-///
-/// 适用于 Java 体质开发同学,常见的集合对象
-///
-///
+//!
+//! 适用于 Java 体质开发同学,常见的集合对象
+//!
 
 /// 普通的 List
 pub type ArrayList<T> = Vec<T>;
@@ -16,10 +16,10 @@ pub mod aeskit {
 
         let mut buf = [0u8; 48];
         let pt_len = plaintext.len();
-        buf[..pt_len].copy_from_slice(&plaintext);
+        buf[..pt_len].copy_from_slice(plaintext);
 
         let ct = Aes256CbcEnc::new(key, iv)
-            .encrypt_padded_b2b_mut::<Pkcs7>(&plaintext, &mut buf)
+            .encrypt_padded_b2b_mut::<Pkcs7>(plaintext, &mut buf)
             .unwrap();
 
         ct.to_vec()
@@ -31,7 +31,7 @@ pub mod aeskit {
         let iv = GenericArray::from_slice(iv);
         let mut buf = [0u8; 48];
         let pt = Aes256CbcDec::new(key, iv)
-            .decrypt_padded_b2b_mut::<Pkcs7>(&ciphertext, &mut buf)
+            .decrypt_padded_b2b_mut::<Pkcs7>(ciphertext, &mut buf)
             .unwrap();
 
         pt.to_vec()
@@ -63,7 +63,7 @@ pub mod rsakit {
 
     // RSA 加密
     pub fn rsa_encrypt(rng: &mut ThreadRng, key: &RsaPublicKey, plaintext: &[u8]) -> Vec<u8> {
-        key.encrypt(rng, Pkcs1v15Encrypt, &plaintext[..])
+        key.encrypt(rng, Pkcs1v15Encrypt, plaintext)
             .expect("failed to encrypt")
     }
 
@@ -1,3 +1,4 @@
+use crate::clockkit::Clock;
 use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -6,6 +7,11 @@ pub fn now() -> DateTime<Local> {
     Local::now()
 }
 
+/// 从指定时钟获取当前时间的 DateTime<Local>
+pub fn now_with(clock: &dyn Clock) -> DateTime<Local> {
+    clock.now().with_timezone(&Local)
+}
+
 /// 格式化时间为字符串，格式示例："2006-01-02 15:04:05"
 pub fn format(dt: DateTime<Local>, fmt: &str) -> String {
     dt.format(fmt).to_string()
@@ -15,7 +15,7 @@ pub fn hash_md5<P: AsRef<Path>>(path: P) -> io::Result<String> {
         if count == 0 {
             break;
         }
-        context.write(&buffer[..count])?;
+        context.write_all(&buffer[..count])?;
     }
     Ok(format!("{:x}", context.finalize()))
 }
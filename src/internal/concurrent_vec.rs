@@ -38,3 +38,9 @@ impl<T> Clone for ConcurrentVec<T> {
         }
     }
 }
+
+impl<T> Default for ConcurrentVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
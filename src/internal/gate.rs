@@ -0,0 +1,63 @@
+use std::sync::{Condvar, Mutex, PoisonError};
+use tokio::sync::Notify;
+
+/// 同步和异步调用方共用的闸门，同一时刻只有一个持有者。
+/// 同步调用方用 `Condvar` 等待，不会像 `tokio::sync::Mutex::blocking_lock` 那样在运行时内 panic
+pub(crate) struct Gate {
+    busy: Mutex<bool>,
+    idle: Condvar,
+    async_idle: Notify,
+}
+
+impl Gate {
+    pub(crate) const fn new() -> Self {
+        Self {
+            busy: Mutex::new(false),
+            idle: Condvar::new(),
+            async_idle: Notify::const_new(),
+        }
+    }
+
+    pub(crate) fn enter(&self) -> GateGuard<'_> {
+        let mut busy = self.busy.lock().unwrap_or_else(PoisonError::into_inner);
+        while *busy {
+            busy = self.idle.wait(busy).unwrap_or_else(PoisonError::into_inner);
+        }
+        *busy = true;
+        GateGuard(self)
+    }
+
+    pub(crate) async fn enter_async(&self) -> GateGuard<'_> {
+        loop {
+            let notified = self.async_idle.notified();
+            tokio::pin!(notified);
+            // 先登记等待再检查，避免错过释放时的通知
+            notified.as_mut().enable();
+            {
+                let mut busy = self.busy.lock().unwrap_or_else(PoisonError::into_inner);
+                if !*busy {
+                    *busy = true;
+                    return GateGuard(self);
+                }
+            }
+            notified.await;
+        }
+    }
+}
+
+impl Default for Gate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 持有闸门，drop 时（包括失败、panic 和 future 被取消）打开
+pub(crate) struct GateGuard<'a>(&'a Gate);
+
+impl Drop for GateGuard<'_> {
+    fn drop(&mut self) {
+        *self.0.busy.lock().unwrap_or_else(PoisonError::into_inner) = false;
+        self.0.idle.notify_all();
+        self.0.async_idle.notify_waiters();
+    }
+}
@@ -1,2 +1,3 @@
 pub mod concurrent_vec;
+pub(crate) mod gate;
 pub(crate) mod snowflake;
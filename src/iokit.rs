@@ -67,6 +67,7 @@ use std::os::windows::fs::{
 };
 
 /// 创建软链接，支持文件和目录，跨平台
+#[allow(clippy::if_same_then_else)]
 pub fn create_symlink<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> io::Result<()> {
     let original = original.as_ref();
     let link = link.as_ref();
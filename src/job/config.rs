@@ -0,0 +1,323 @@
+use crate::configkit;
+use crate::jobkit::{AsyncJobFn, JobKit, JobOptions, JobResult, JobTask, JobType};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tokio::time;
+
+/// A job declared in a config file, bound to a registered handler by name.
+/// Unknown keys are rejected so a misspelled option isn't silently dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawDefinition")]
+pub struct JobDefinition {
+    pub name: String,
+    /// Registered handler name, defaults to the job name
+    #[serde(default)]
+    pub handler: Option<String>,
+    pub job_type: JobType,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    /// `retry`, `timezone` and the other options sit next to the fields above
+    #[serde(flatten)]
+    pub options: JobOptions,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+/// `JobDefinition` plus whatever keys neither it nor `JobOptions` know,
+/// `deny_unknown_fields` doesn't work next to `flatten`
+#[derive(Deserialize)]
+struct RawDefinition {
+    name: String,
+    #[serde(default)]
+    handler: Option<String>,
+    job_type: JobType,
+    #[serde(default = "enabled_default")]
+    enabled: bool,
+    #[serde(flatten)]
+    options: JobOptions,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, IgnoredAny>,
+}
+
+impl TryFrom<RawDefinition> for JobDefinition {
+    type Error = String;
+
+    fn try_from(raw: RawDefinition) -> Result<Self, String> {
+        if let Some(key) = raw.unknown.keys().next() {
+            return Err(format!("Unknown field {} in job {}", key, raw.name));
+        }
+        Ok(Self {
+            name: raw.name,
+            handler: raw.handler,
+            job_type: raw.job_type,
+            enabled: raw.enabled,
+            options: raw.options,
+        })
+    }
+}
+
+impl JobDefinition {
+    fn handler_name(&self) -> &str {
+        self.handler.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Contents of a job config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobConfig {
+    #[serde(default)]
+    pub jobs: Vec<JobDefinition>,
+}
+
+impl JobConfig {
+    /// Load from a JSON file, or a YAML file when the `yaml` feature is enabled
+    /// and the extension is `.yaml` or `.yml`. YAML writes enum variants as
+    /// tags, e.g. `job_type: !Cron "0 0 8 * * *"`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let yaml = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("yaml" | "yml")
+        );
+        let config = match yaml {
+            #[cfg(feature = "yaml")]
+            true => configkit::from_yaml_file(path),
+            #[cfg(not(feature = "yaml"))]
+            true => return Err("YAML job config needs the `yaml` feature".to_string()),
+            false => configkit::from_json_file(path),
+        };
+        config.map_err(|e| format!("Failed to load {}: {}", path.display(), e))
+    }
+}
+
+/// Handlers that config files refer to by name
+#[derive(Default)]
+pub struct JobRegistry {
+    handlers: HashMap<String, JobTask>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a sync handler, replacing one with the same name
+    pub fn register(
+        mut self,
+        name: &str,
+        task: impl Fn() -> JobResult + Send + Sync + 'static,
+    ) -> Self {
+        self.handlers
+            .insert(name.to_string(), JobTask::Sync(Arc::new(task)));
+        self
+    }
+
+    /// Register an async handler, replacing one with the same name
+    pub fn register_async<F, Fut>(mut self, name: &str, task: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = JobResult> + Send + 'static,
+    {
+        let task: Arc<AsyncJobFn> = Arc::new(move || Box::pin(task()));
+        self.handlers.insert(name.to_string(), JobTask::Async(task));
+        self
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    fn get(&self, name: &str) -> Option<JobTask> {
+        self.handlers.get(name).cloned()
+    }
+}
+
+/// Jobs changed by one load
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Keeps the jobs of a `JobKit` in line with a config file. Only jobs added by
+/// the loader are updated or removed, jobs added in code are left alone.
+pub struct JobLoader {
+    path: PathBuf,
+    registry: JobRegistry,
+    /// Serialized definitions of the jobs the loader added, by name
+    applied: Mutex<HashMap<String, String>>,
+}
+
+impl JobLoader {
+    pub fn new<P: Into<PathBuf>>(path: P, registry: JobRegistry) -> Self {
+        Self {
+            path: path.into(),
+            registry,
+            applied: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the file and apply it to `kit`
+    pub fn load(&self, kit: &JobKit) -> Result<ReloadReport, String> {
+        let config = JobConfig::from_file(&self.path)?;
+        self.apply(kit, &config)
+    }
+
+    /// Add new jobs, restart changed ones and remove the ones that are gone or
+    /// disabled. An invalid config changes nothing: it is checked up front, and
+    /// if adding a job still fails the jobs added or restarted by this load are
+    /// rolled back before anything is removed.
+    pub fn apply(&self, kit: &JobKit, config: &JobConfig) -> Result<ReloadReport, String> {
+        let mut names = HashSet::new();
+        for def in &config.jobs {
+            if !names.insert(def.name.as_str()) {
+                return Err(format!("Duplicate job: {}", def.name));
+            }
+            if !self.registry.contains(def.handler_name()) {
+                return Err(format!(
+                    "Unknown handler {} for job {}",
+                    def.handler_name(),
+                    def.name
+                ));
+            }
+            kit.check_definition(&def.job_type, &def.options)
+                .map_err(|e| format!("Invalid job {}: {}", def.name, e))?;
+        }
+
+        let mut applied = self.applied.lock().unwrap();
+        // jobs the loader does not own must not clash with the config
+        for def in config.jobs.iter().filter(|def| def.enabled) {
+            if !applied.contains_key(&def.name) {
+                kit.check_name(&def.name)
+                    .map_err(|e| format!("Invalid job {}: {}", def.name, e))?;
+            }
+        }
+
+        let mut report = ReloadReport::default();
+        // name and previous definition of every job this load started
+        let mut changed: Vec<(String, Option<String>)> = Vec::new();
+        for def in config.jobs.iter().filter(|def| def.enabled) {
+            let serialized = serde_json::to_string(def).map_err(|e| e.to_string())?;
+            let old = match applied.get(&def.name) {
+                Some(old) if *old == serialized => continue,
+                Some(old) => {
+                    kit.stop(&def.name);
+                    Some(old.clone())
+                }
+                None => None,
+            };
+            if let Err(e) = self.add(kit, def) {
+                changed.push((def.name.clone(), old));
+                self.rollback(kit, &mut applied, changed);
+                return Err(format!("Failed to add job {}: {}", def.name, e));
+            }
+            match &old {
+                Some(_) => report.updated.push(def.name.clone()),
+                None => report.added.push(def.name.clone()),
+            }
+            changed.push((def.name.clone(), old));
+            applied.insert(def.name.clone(), serialized);
+        }
+
+        let wanted: HashSet<&str> = config
+            .jobs
+            .iter()
+            .filter(|def| def.enabled)
+            .map(|def| def.name.as_str())
+            .collect();
+        let mut gone: Vec<String> = applied
+            .keys()
+            .filter(|name| !wanted.contains(name.as_str()))
+            .cloned()
+            .collect();
+        gone.sort();
+        for name in gone {
+            kit.remove(&name);
+            applied.remove(&name);
+            report.removed.push(name);
+        }
+        Ok(report)
+    }
+
+    /// Undo the jobs started by a failed load, newest first: added jobs are
+    /// removed and restarted ones get their previous definition back. A job
+    /// whose previous definition can't be restored is forgotten, so the next
+    /// load adds it again.
+    fn rollback(
+        &self,
+        kit: &JobKit,
+        applied: &mut HashMap<String, String>,
+        changed: Vec<(String, Option<String>)>,
+    ) {
+        for (name, old) in changed.into_iter().rev() {
+            let Some(old) = old else {
+                kit.remove(&name);
+                applied.remove(&name);
+                continue;
+            };
+            kit.stop(&name);
+            let restored = serde_json::from_str::<JobDefinition>(&old)
+                .is_ok_and(|def| self.add(kit, &def).is_ok());
+            match restored {
+                true => applied.insert(name, old),
+                false => applied.remove(&name),
+            };
+        }
+    }
+
+    fn add(&self, kit: &JobKit, def: &JobDefinition) -> Result<String, String> {
+        let task = self
+            .registry
+            .get(def.handler_name())
+            .ok_or_else(|| format!("Unknown handler {}", def.handler_name()))?;
+        kit.add_task(&def.name, def.job_type.clone(), def.options.clone(), task)
+    }
+
+    /// Reload whenever the file changes, polling every `interval`. A bad file is
+    /// logged and the running jobs are kept.
+    pub fn watch(self: &Arc<Self>, kit: Arc<JobKit>, interval: Duration) -> JoinHandle<()> {
+        let loader = self.clone();
+        let mut version = loader.version();
+        tokio::spawn(async move {
+            let mut ticker = time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let current = loader.version();
+                if current == version {
+                    continue;
+                }
+                version = current;
+                match loader.load(&kit) {
+                    Ok(report) => log::info!(
+                        "[Job Config Reload] added = {:?}, updated = {:?}, removed = {:?}",
+                        report.added,
+                        report.updated,
+                        report.removed
+                    ),
+                    Err(e) => log::error!("[Job Config Reload Error] {}", e),
+                }
+            }
+        })
+    }
+
+    /// Modification time and length of the file, `None` if it can't be read
+    fn version(&self) -> Option<(SystemTime, u64)> {
+        let meta = std::fs::metadata(&self.path).ok()?;
+        Some((meta.modified().ok()?, meta.len()))
+    }
+}
@@ -0,0 +1,141 @@
+use crate::jobkit::JobTimeZone;
+use serde::de::value::{EnumAccessDeserializer, MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{self, Deserializer, EnumAccess, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::fmt;
+use std::time::Duration;
+
+/// Parse a duration written as whole seconds (`"90"`) or as numbers with
+/// units, e.g. `"500ms"`, `"30s"`, `"1h 30m"`. Units are `ms`, `s`, `m`, `h`
+/// and `d`.
+pub(crate) fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    if let Ok(secs) = text.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+    let invalid = || format!("Invalid duration: {:?}", text);
+    let mut total = Duration::ZERO;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let value: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let part = match &rest[..unit_len] {
+            "ms" => Some(Duration::from_millis(value)),
+            "s" => Some(Duration::from_secs(value)),
+            "m" => value.checked_mul(60).map(Duration::from_secs),
+            "h" => value.checked_mul(3600).map(Duration::from_secs),
+            "d" => value.checked_mul(86400).map(Duration::from_secs),
+            _ => None,
+        };
+        total = part
+            .and_then(|part| total.checked_add(part))
+            .ok_or_else(invalid)?;
+        rest = rest[unit_len..].trim_start();
+    }
+    Ok(total)
+}
+
+/// Deserialize a duration from seconds, a string accepted by `parse_duration`,
+/// or serde's `{ "secs": .., "nanos": .. }` form that stored jobs use
+pub(crate) fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    deserializer.deserialize_any(DurationVisitor)
+}
+
+/// `duration` for optional fields
+pub(crate) fn option_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    #[derive(Deserialize)]
+    struct Lenient(#[serde(deserialize_with = "duration")] Duration);
+
+    Ok(Option::<Lenient>::deserialize(deserializer)?.map(|lenient| lenient.0))
+}
+
+struct DurationVisitor;
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("seconds or a duration such as \"30s\"")
+    }
+
+    fn visit_u64<E: de::Error>(self, secs: u64) -> Result<Duration, E> {
+        Ok(Duration::from_secs(secs))
+    }
+
+    fn visit_i64<E: de::Error>(self, secs: i64) -> Result<Duration, E> {
+        u64::try_from(secs)
+            .map(Duration::from_secs)
+            .map_err(|_| E::custom(format!("Invalid duration: {}", secs)))
+    }
+
+    fn visit_f64<E: de::Error>(self, secs: f64) -> Result<Duration, E> {
+        Duration::try_from_secs_f64(secs)
+            .map_err(|_| E::custom(format!("Invalid duration: {}", secs)))
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<Duration, E> {
+        parse_duration(text).map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Duration, A::Error> {
+        Duration::deserialize(MapAccessDeserializer::new(map))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Duration, A::Error> {
+        Duration::deserialize(SeqAccessDeserializer::new(seq))
+    }
+}
+
+/// The derived form, `"Utc"`, `"Local"` or `{ "Fixed": 28800 }`
+#[derive(Deserialize)]
+enum TaggedTimeZone {
+    Utc,
+    Local,
+    Fixed(i32),
+}
+
+impl TaggedTimeZone {
+    fn into_time_zone<E: de::Error>(self) -> Result<JobTimeZone, E> {
+        match self {
+            TaggedTimeZone::Utc => Ok(JobTimeZone::Utc),
+            TaggedTimeZone::Local => Ok(JobTimeZone::Local),
+            TaggedTimeZone::Fixed(secs) => JobTimeZone::fixed(secs).map_err(E::custom),
+        }
+    }
+}
+
+/// Accepts `"UTC"`, `"Local"`, an offset such as `"+08:00"` or `"UTC-5"`,
+/// and the derived tagged form
+impl<'de> Deserialize<'de> for JobTimeZone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TimeZoneVisitor)
+    }
+}
+
+struct TimeZoneVisitor;
+
+impl<'de> Visitor<'de> for TimeZoneVisitor {
+    type Value = JobTimeZone;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("\"UTC\", \"Local\" or an offset such as \"+08:00\"")
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<JobTimeZone, E> {
+        text.parse().map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<JobTimeZone, A::Error> {
+        TaggedTimeZone::deserialize(MapAccessDeserializer::new(map))?.into_time_zone()
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<JobTimeZone, A::Error> {
+        TaggedTimeZone::deserialize(EnumAccessDeserializer::new(data))?.into_time_zone()
+    }
+}
@@ -0,0 +1,191 @@
+use crate::jobkit::JobRecord;
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Which records a `HistoryQuery` keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFilter {
+    Success,
+    /// Failed, panicked or timed out, skipped records excluded
+    Failure,
+    TimedOut,
+    Skipped,
+}
+
+impl RecordFilter {
+    fn matches(&self, record: &JobRecord) -> bool {
+        match self {
+            RecordFilter::Success => record.result.is_ok(),
+            RecordFilter::Failure => record.result.is_err() && !record.skipped,
+            RecordFilter::TimedOut => record.timed_out,
+            RecordFilter::Skipped => record.skipped,
+        }
+    }
+}
+
+/// Filter for `JobHistory::query`, all conditions are combined
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    /// Records started at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Records started before this time
+    pub until: Option<DateTime<Utc>>,
+    pub filter: Option<RecordFilter>,
+    /// Keep only the newest `limit` matches
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    pub fn filter(mut self, filter: RecordFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn matches(&self, record: &JobRecord) -> bool {
+        self.since.is_none_or(|since| record.timestamp >= since)
+            && self.until.is_none_or(|until| record.timestamp < until)
+            && self.filter.is_none_or(|filter| filter.matches(record))
+    }
+}
+
+/// Aggregated statistics of a job's history, skipped records only count in `skipped`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JobStats {
+    /// Executed runs
+    pub runs: usize,
+    pub successes: usize,
+    pub failures: usize,
+    pub skipped: usize,
+    /// `successes / runs`, `0.0` without runs
+    pub success_rate: f64,
+    pub p50_duration: Duration,
+    pub p95_duration: Duration,
+    pub max_duration: Duration,
+    /// Failures since the last success
+    pub consecutive_failures: usize,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_failure: Option<DateTime<Utc>>,
+}
+
+/// Ring buffer of execution records, the oldest record is dropped when full
+#[derive(Debug, Clone)]
+pub struct JobHistory {
+    records: VecDeque<JobRecord>,
+    capacity: usize,
+}
+
+impl JobHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: VecDeque::with_capacity(capacity.min(1024)),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, record: JobRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Records, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &JobRecord> {
+        self.records.iter()
+    }
+
+    pub fn to_vec(&self) -> Vec<JobRecord> {
+        self.records.iter().cloned().collect()
+    }
+
+    /// Matching records, oldest first
+    pub fn query(&self, query: &HistoryQuery) -> Vec<JobRecord> {
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut matched: Vec<JobRecord> = self
+            .records
+            .iter()
+            .rev()
+            .filter(|r| query.matches(r))
+            .take(limit)
+            .cloned()
+            .collect();
+        matched.reverse();
+        matched
+    }
+
+    /// The newest `n` failures, oldest first
+    pub fn last_failures(&self, n: usize) -> Vec<JobRecord> {
+        self.query(&HistoryQuery::new().filter(RecordFilter::Failure).limit(n))
+    }
+
+    pub fn stats(&self) -> JobStats {
+        let mut stats = JobStats::default();
+        let mut durations = Vec::with_capacity(self.records.len());
+        for record in self.records.iter() {
+            if record.skipped {
+                stats.skipped += 1;
+                continue;
+            }
+            stats.runs += 1;
+            durations.push(record.duration);
+            if record.result.is_ok() {
+                stats.successes += 1;
+                stats.consecutive_failures = 0;
+                stats.last_success = Some(record.timestamp);
+            } else {
+                stats.failures += 1;
+                stats.consecutive_failures += 1;
+                stats.last_failure = Some(record.timestamp);
+            }
+        }
+        if stats.runs > 0 {
+            stats.success_rate = stats.successes as f64 / stats.runs as f64;
+            durations.sort();
+            stats.p50_duration = percentile(&durations, 50);
+            stats.p95_duration = percentile(&durations, 95);
+            stats.max_duration = durations[durations.len() - 1];
+        }
+        stats
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty values
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
@@ -0,0 +1,62 @@
+use crate::jobkit::JobRecord;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Receives job lifecycle events, every method defaults to a no-op
+pub trait JobListener: Send + Sync {
+    /// A run is about to start
+    fn on_start(&self, _name: &str, _time: DateTime<Utc>) {}
+
+    /// A run finished successfully
+    fn on_success(&self, _record: &JobRecord) {}
+
+    /// A run failed, panicked or timed out after all retries
+    fn on_failure(&self, _record: &JobRecord) {}
+
+    /// An attempt failed and will be retried after `delay`
+    fn on_retry(&self, _name: &str, _attempt: u32, _delay: Duration, _error: &str) {}
+
+    /// A run was skipped, e.g. overlap or failed workflow upstream
+    fn on_skip(&self, _name: &str, _reason: &str) {}
+}
+
+/// Default listener, writes events through `log`
+pub struct LogListener;
+
+impl JobListener for LogListener {
+    fn on_start(&self, name: &str, time: DateTime<Utc>) {
+        log::debug!("[Task Start] name = {}, time = {}", name, time);
+    }
+
+    fn on_success(&self, record: &JobRecord) {
+        log::info!(
+            "[Task Success] name = {}, time = {}, duration = {:?}",
+            record.name,
+            record.timestamp,
+            record.duration
+        );
+    }
+
+    fn on_failure(&self, record: &JobRecord) {
+        log::error!(
+            "[Task Error] name = {}, time = {}, e = {}",
+            record.name,
+            record.timestamp,
+            record.result.as_ref().err().map_or("", |e| e.as_str())
+        );
+    }
+
+    fn on_retry(&self, name: &str, attempt: u32, delay: Duration, error: &str) {
+        log::warn!(
+            "[Task Retry] name = {}, attempt = {}, delay = {:?}, e = {}",
+            name,
+            attempt,
+            delay,
+            error
+        );
+    }
+
+    fn on_skip(&self, name: &str, reason: &str) {
+        log::info!("[Task Skip] name = {}, reason = {}", name, reason);
+    }
+}
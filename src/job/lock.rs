@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+#[cfg(unix)]
+use std::fs::{self, File, OpenOptions};
+use std::io;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Cluster-wide lock taken around singleton job runs
+pub trait JobLock: Send + Sync {
+    /// Try to take the lock for `key` for at most `ttl`, returns `false` when it
+    /// is held elsewhere, including by a run of this instance
+    fn try_lock(&self, key: &str, ttl: Duration) -> io::Result<bool>;
+    /// Release the lock for `key` if this instance holds it
+    fn unlock(&self, key: &str) -> io::Result<()>;
+}
+
+/// `flock` on `<dir>/<key>.lock`, the directory is shared between instances.
+/// The OS drops the lock when the process dies, so `ttl` is not used.
+#[cfg(unix)]
+pub struct FileJobLock {
+    dir: PathBuf,
+    held: Mutex<HashMap<String, File>>,
+}
+
+#[cfg(unix)]
+impl FileJobLock {
+    /// Use `dir` for lock files, created if missing
+    pub fn new<P: Into<PathBuf>>(dir: P) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            held: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn lock_path(&self, key: &str) -> PathBuf {
+        let file: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{}.lock", file))
+    }
+}
+
+#[cfg(unix)]
+impl JobLock for FileJobLock {
+    fn try_lock(&self, key: &str, _ttl: Duration) -> io::Result<bool> {
+        let mut held = self.held.lock().unwrap();
+        if held.contains_key(key) {
+            return Ok(false);
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.lock_path(key))?;
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+            let e = io::Error::last_os_error();
+            return match e.kind() {
+                io::ErrorKind::WouldBlock => Ok(false),
+                _ => Err(e),
+            };
+        }
+        held.insert(key.to_string(), file);
+        Ok(true)
+    }
+
+    fn unlock(&self, key: &str) -> io::Result<()> {
+        if let Some(file) = self.held.lock().unwrap().remove(key) {
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_UN) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Key value storage with expiring entries, backs `LeaseJobLock`
+pub trait LeaseStore: Send + Sync {
+    /// Set `key` to `owner` for `ttl` if it is free or expired, as one atomic step
+    fn acquire(&self, key: &str, owner: &str, ttl: Duration) -> io::Result<bool>;
+    /// Delete `key` if it is still set to `owner`
+    fn release(&self, key: &str, owner: &str) -> io::Result<()>;
+}
+
+/// Lease based lock, a run holds the lease at most `ttl` even if its instance dies
+pub struct LeaseJobLock {
+    store: Arc<dyn LeaseStore>,
+    owner: String,
+}
+
+impl LeaseJobLock {
+    /// Lock on `store` with a random owner id
+    pub fn new(store: Arc<dyn LeaseStore>) -> Self {
+        Self::with_owner(store, &Uuid::new_v4().to_string())
+    }
+
+    /// Lock on `store` as `owner`, which must be unique per instance
+    pub fn with_owner(store: Arc<dyn LeaseStore>, owner: &str) -> Self {
+        Self {
+            store,
+            owner: owner.to_string(),
+        }
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+}
+
+impl JobLock for LeaseJobLock {
+    fn try_lock(&self, key: &str, ttl: Duration) -> io::Result<bool> {
+        self.store.acquire(key, &self.owner, ttl)
+    }
+
+    fn unlock(&self, key: &str) -> io::Result<()> {
+        self.store.release(key, &self.owner)
+    }
+}
+
+/// In-memory `LeaseStore`, share one between `JobKit`s to stand in for a KV store
+#[derive(Default)]
+pub struct MemoryLeaseStore {
+    leases: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl MemoryLeaseStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current owner of `key`, `None` when free or expired
+    pub fn owner(&self, key: &str) -> Option<String> {
+        let leases = self.leases.lock().unwrap();
+        leases
+            .get(key)
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(owner, _)| owner.clone())
+    }
+}
+
+impl LeaseStore for MemoryLeaseStore {
+    fn acquire(&self, key: &str, owner: &str, ttl: Duration) -> io::Result<bool> {
+        let mut leases = self.leases.lock().unwrap();
+        let now = Instant::now();
+        if leases.get(key).is_some_and(|(_, expires)| *expires > now) {
+            return Ok(false);
+        }
+        leases.insert(key.to_string(), (owner.to_string(), now + ttl));
+        Ok(true)
+    }
+
+    fn release(&self, key: &str, owner: &str) -> io::Result<()> {
+        let mut leases = self.leases.lock().unwrap();
+        if leases.get(key).is_some_and(|(held, _)| held == owner) {
+            leases.remove(key);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+pub(crate) mod config;
+pub(crate) mod format;
+pub(crate) mod history;
+pub(crate) mod listener;
+pub(crate) mod lock;
+pub(crate) mod store;
+pub(crate) mod workflow;
@@ -0,0 +1,146 @@
+use crate::jobkit::{JobOptions, JobRecord, JobType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Persisted job definition, the job body is bound again by name on startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredJob {
+    pub id: String,
+    pub name: String,
+    pub job_type: JobType,
+    pub options: JobOptions,
+    pub created_at: DateTime<Utc>,
+    /// Last run when the definition was saved, newer runs are read from the history
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+/// Storage for job definitions and history
+pub trait JobStore: Send + Sync {
+    /// Insert or replace a job definition
+    fn save_job(&self, job: &StoredJob) -> io::Result<()>;
+    /// Load all job definitions
+    fn load_jobs(&self) -> io::Result<Vec<StoredJob>>;
+    /// Remove a job definition and its history
+    fn remove_job(&self, id: &str) -> io::Result<()>;
+    /// Append one execution record
+    fn append_record(&self, id: &str, record: &JobRecord) -> io::Result<()>;
+    /// Load the history of a job, oldest first
+    fn load_history(&self, id: &str) -> io::Result<Vec<JobRecord>>;
+    /// Replace the history of a job
+    fn save_history(&self, id: &str, records: &[JobRecord]) -> io::Result<()>;
+}
+
+/// File backed store: `jobs.jsonl` holds definitions, `history/<id>.jsonl` holds records
+pub struct FileJobStore {
+    dir: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileJobStore {
+    /// Open a store in `dir`, created if missing
+    pub fn new<P: Into<PathBuf>>(dir: P) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join("history"))?;
+        Ok(Self {
+            dir,
+            lock: Mutex::new(()),
+        })
+    }
+
+    fn jobs_path(&self) -> PathBuf {
+        self.dir.join("jobs.jsonl")
+    }
+
+    fn history_path(&self, id: &str) -> PathBuf {
+        self.dir.join("history").join(format!("{}.jsonl", id))
+    }
+
+    fn read_lines<T: for<'de> Deserialize<'de>>(path: &PathBuf) -> io::Result<Vec<T>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut items = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let item = serde_json::from_str(&line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    /// Write to a temp file and rename, so a crash never leaves a half written file
+    fn write_lines<T: Serialize>(path: &PathBuf, items: &[T]) -> io::Result<()> {
+        let tmp = path.with_extension("jsonl.tmp");
+        {
+            let mut file = File::create(&tmp)?;
+            for item in items {
+                let line = serde_json::to_string(item)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                writeln!(file, "{}", line)?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(tmp, path)
+    }
+}
+
+impl JobStore for FileJobStore {
+    fn save_job(&self, job: &StoredJob) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        let path = self.jobs_path();
+        let mut jobs: Vec<StoredJob> = Self::read_lines(&path)?;
+        match jobs.iter_mut().find(|j| j.id == job.id) {
+            Some(existing) => *existing = job.clone(),
+            None => jobs.push(job.clone()),
+        }
+        Self::write_lines(&path, &jobs)
+    }
+
+    fn load_jobs(&self) -> io::Result<Vec<StoredJob>> {
+        let _guard = self.lock.lock().unwrap();
+        Self::read_lines(&self.jobs_path())
+    }
+
+    fn remove_job(&self, id: &str) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        let path = self.jobs_path();
+        let mut jobs: Vec<StoredJob> = Self::read_lines(&path)?;
+        jobs.retain(|j| j.id != id);
+        Self::write_lines(&path, &jobs)?;
+        match fs::remove_file(self.history_path(id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn append_record(&self, id: &str, record: &JobRecord) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        let line = serde_json::to_string(record)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.history_path(id))?;
+        writeln!(file, "{}", line)
+    }
+
+    fn load_history(&self, id: &str) -> io::Result<Vec<JobRecord>> {
+        let _guard = self.lock.lock().unwrap();
+        Self::read_lines(&self.history_path(id))
+    }
+
+    fn save_history(&self, id: &str, records: &[JobRecord]) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        Self::write_lines(&self.history_path(id), records)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::jobkit::{AsyncJobFn, JobOptions, JobResult, JobTask};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+/// A step of a workflow
+pub(crate) struct WorkflowStep {
+    pub(crate) name: String,
+    /// Indices of upstream steps, resolved by `Workflow::validate`
+    pub(crate) deps: Vec<usize>,
+    pub(crate) dep_names: Vec<String>,
+    pub(crate) options: JobOptions,
+    pub(crate) task: JobTask,
+}
+
+/// A DAG of steps scheduled as a single job, see `JobKit::add_workflow`
+///
+/// ```
+/// use rovkit::jobkit::Workflow;
+///
+/// let workflow = Workflow::new("nightly")
+///     .step("fetch", &[], || Ok(()))
+///     .step("transform", &["fetch"], || Ok(()))
+///     .step("compress", &["transform"], || Ok(()))
+///     .step("upload", &["compress"], || Ok(()));
+/// ```
+pub struct Workflow {
+    pub(crate) name: String,
+    pub(crate) steps: Vec<WorkflowStep>,
+}
+
+impl Workflow {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Add a step that runs after all `deps` succeeded
+    pub fn step(
+        self,
+        name: &str,
+        deps: &[&str],
+        task: impl Fn() -> JobResult + Send + Sync + 'static,
+    ) -> Self {
+        self.step_with_options(name, deps, JobOptions::default(), task)
+    }
+
+    /// Add a step with options, e.g. its own retry policy
+    pub fn step_with_options(
+        self,
+        name: &str,
+        deps: &[&str],
+        options: JobOptions,
+        task: impl Fn() -> JobResult + Send + Sync + 'static,
+    ) -> Self {
+        self.push(name, deps, options, JobTask::Sync(Arc::new(task)))
+    }
+
+    /// Add an async step
+    pub fn async_step<F, Fut>(self, name: &str, deps: &[&str], task: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = JobResult> + Send + 'static,
+    {
+        self.async_step_with_options(name, deps, JobOptions::default(), task)
+    }
+
+    /// Add an async step with options
+    pub fn async_step_with_options<F, Fut>(
+        self,
+        name: &str,
+        deps: &[&str],
+        options: JobOptions,
+        task: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = JobResult> + Send + 'static,
+    {
+        let task: Arc<AsyncJobFn> = Arc::new(move || Box::pin(task()));
+        self.push(name, deps, options, JobTask::Async(task))
+    }
+
+    fn push(mut self, name: &str, deps: &[&str], options: JobOptions, task: JobTask) -> Self {
+        self.steps.push(WorkflowStep {
+            name: name.to_string(),
+            deps: Vec::new(),
+            dep_names: deps.iter().map(|d| d.to_string()).collect(),
+            options,
+            task,
+        });
+        self
+    }
+
+    /// Resolve dependencies, fails on empty workflows, duplicate names,
+    /// unknown steps and cycles
+    pub(crate) fn validate(&mut self) -> Result<(), String> {
+        if self.steps.is_empty() {
+            return Err(format!("Workflow {} has no steps", self.name));
+        }
+        let mut index = HashMap::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            if index.insert(step.name.clone(), i).is_some() {
+                return Err(format!("Duplicate workflow step: {}", step.name));
+            }
+        }
+        for step in self.steps.iter_mut() {
+            step.deps =
+                step.dep_names
+                    .iter()
+                    .map(|dep| {
+                        index.get(dep).copied().ok_or_else(|| {
+                            format!("Unknown upstream step {} of {}", dep, step.name)
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+        }
+
+        // Kahn's algorithm
+        let mut in_degree: Vec<usize> = self.steps.iter().map(|s| s.deps.len()).collect();
+        let mut ready: Vec<usize> = (0..self.steps.len())
+            .filter(|&i| in_degree[i] == 0)
+            .collect();
+        let mut visited = 0;
+        while let Some(i) = ready.pop() {
+            visited += 1;
+            for (j, step) in self.steps.iter().enumerate() {
+                for _ in step.deps.iter().filter(|&&d| d == i) {
+                    in_degree[j] -= 1;
+                    if in_degree[j] == 0 {
+                        ready.push(j);
+                    }
+                }
+            }
+        }
+        if visited != self.steps.len() {
+            return Err(format!("Workflow {} has a dependency cycle", self.name));
+        }
+        Ok(())
+    }
+}
@@ -1,37 +1,382 @@
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError, RwLock,
+    },
     time::{Duration, Instant},
 };
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Local, Utc};
 use cron::Schedule;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
-use tokio::task::JoinHandle;
+use tokio::sync::{watch, Semaphore};
+use tokio::task::{AbortHandle, JoinError, JoinHandle};
 use tokio::time;
 use uuid::Uuid;
 
-/// Max number of job history records to keep
+use crate::clockkit::{self, Clock};
+use crate::job::format;
+
+pub use crate::job::config::{JobConfig, JobDefinition, JobLoader, JobRegistry, ReloadReport};
+pub use crate::job::history::{HistoryQuery, JobHistory, JobStats, RecordFilter};
+pub use crate::job::listener::{JobListener, LogListener};
+#[cfg(unix)]
+pub use crate::job::lock::FileJobLock;
+pub use crate::job::lock::{JobLock, LeaseJobLock, LeaseStore, MemoryLeaseStore};
+pub use crate::job::store::{FileJobStore, JobStore, StoredJob};
+pub use crate::job::workflow::Workflow;
+
+/// Default number of history records kept per job
 pub const MAX_HISTORY_RECORDS: usize = 1000;
 
-/// Job type: Interval or Cron
-#[derive(Clone)]
+/// Job type: Interval, Cron or one-shot. Durations in config files can be
+/// written as seconds or with units, e.g. `{ "Interval": "30s" }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub enum JobType {
-    Interval(Duration),
+    Interval(#[serde(deserialize_with = "format::duration")] Duration),
+    /// Cron expression with seconds, evaluated in the job's `JobTimeZone`
     Cron(String),
+    /// Run once at the given time, right away if it already passed
+    At(DateTime<Utc>),
+    /// Run once after the delay
+    Delay(#[serde(deserialize_with = "format::duration")] Duration),
+    /// Run every `interval`, the first run after `delay`
+    DelayedInterval {
+        #[serde(deserialize_with = "format::duration")]
+        delay: Duration,
+        #[serde(deserialize_with = "format::duration")]
+        interval: Duration,
+    },
+}
+
+/// Time zone used to evaluate cron expressions. Config files write it as
+/// `"UTC"`, `"Local"` or an offset such as `"+08:00"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum JobTimeZone {
+    #[default]
+    Utc,
+    /// The system local time zone
+    Local,
+    /// Fixed offset in seconds east of UTC
+    Fixed(i32),
+}
+
+impl JobTimeZone {
+    /// Fixed offset in seconds east of UTC, within a day either way
+    pub fn fixed(secs: i32) -> Result<Self, String> {
+        FixedOffset::east_opt(secs)
+            .map(|_| JobTimeZone::Fixed(secs))
+            .ok_or_else(|| format!("Time zone offset out of range: {}s", secs))
+    }
+
+    /// Fixed offset in hours, e.g. `JobTimeZone::hours(8)` for UTC+8, within
+    /// a day either way like `fixed`
+    pub fn hours(hours: i32) -> Result<Self, String> {
+        hours
+            .checked_mul(3600)
+            .ok_or_else(|| format!("Time zone offset out of range: {}h", hours))
+            .and_then(Self::fixed)
+    }
+
+    /// Next fire time of `schedule` strictly after `after`
+    pub fn next_fire(&self, schedule: &Schedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            JobTimeZone::Utc => schedule.after(&after).next(),
+            JobTimeZone::Local => schedule
+                .after(&after.with_timezone(&Local))
+                .next()
+                .map(|t| t.with_timezone(&Utc)),
+            JobTimeZone::Fixed(secs) => {
+                let offset = FixedOffset::east_opt(*secs)?;
+                schedule
+                    .after(&after.with_timezone(&offset))
+                    .next()
+                    .map(|t| t.with_timezone(&Utc))
+            }
+        }
+    }
+}
+
+impl FromStr for JobTimeZone {
+    type Err = String;
+
+    /// `UTC`, `Local`, or an offset such as `+08:00`, `-0530` or `UTC+8`
+    fn from_str(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        match text.to_ascii_lowercase().as_str() {
+            "utc" | "z" => return Ok(JobTimeZone::Utc),
+            "local" => return Ok(JobTimeZone::Local),
+            _ => {}
+        }
+        let invalid = || format!("Invalid time zone: {:?}", text);
+        let offset = text
+            .strip_prefix("UTC")
+            .or_else(|| text.strip_prefix("GMT"))
+            .unwrap_or(text);
+        let (sign, digits) = match offset.split_at_checked(1) {
+            Some(("+", digits)) => (1, digits),
+            Some(("-", digits)) => (-1, digits),
+            _ => return Err(invalid()),
+        };
+        let (hours, minutes) = match digits.split_once(':') {
+            Some(parts) => parts,
+            None if digits.len() == 4 => digits.split_at(2),
+            None => (digits, "0"),
+        };
+        let number = |part: &str| match part.bytes().all(|b| b.is_ascii_digit()) {
+            true => part.parse::<i32>().ok(),
+            false => None,
+        };
+        match (number(hours), number(minutes)) {
+            (Some(hours), Some(minutes)) if hours <= 24 && minutes < 60 => {
+                Self::fixed(sign * (hours * 3600 + minutes * 60))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// What to do when a run is still in progress at the next tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OverlapPolicy {
+    /// Drop the tick, the job keeps a single run in flight
+    Skip,
+    /// Wait for the previous run to finish, then run (ticks queue up)
+    #[default]
+    Queue,
+    /// Start another run concurrently
+    Allow,
+}
+
+/// Delay between retry attempts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum Backoff {
+    /// Same delay before every retry
+    Fixed(#[serde(deserialize_with = "format::duration")] Duration),
+    /// `initial * 2^(attempt - 1)`, capped at `max`
+    Exponential {
+        #[serde(deserialize_with = "format::duration")]
+        initial: Duration,
+        #[serde(deserialize_with = "format::duration")]
+        max: Duration,
+    },
+}
+
+/// Retry policy for failed, panicked or timed out runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// Total attempts per run, including the first one
+    pub max_attempts: u32,
+    pub backoff: Backoff,
+    /// Randomize each delay into `[delay / 2, delay]`
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Retry with a fixed delay
+    pub fn fixed(max_attempts: u32, delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            backoff: Backoff::Fixed(delay),
+            jitter: false,
+        }
+    }
+
+    /// Retry with an exponential delay
+    pub fn exponential(max_attempts: u32, initial: Duration, max: Duration) -> Self {
+        Self {
+            max_attempts,
+            backoff: Backoff::Exponential { initial, max },
+            jitter: false,
+        }
+    }
+
+    /// Enable jitter
+    pub fn with_jitter(mut self) -> Self {
+        self.jitter = true;
+        self
+    }
+
+    /// Delay before the next attempt, `attempt` is the 1-based attempt that just failed
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+                initial.saturating_mul(factor).min(max)
+            }
+        };
+        if self.jitter && !delay.is_zero() {
+            let half = delay / 2;
+            half + rand::thread_rng().gen_range(Duration::ZERO..=delay - half)
+        } else {
+            delay
+        }
+    }
+}
+
+/// What to do on startup when a persisted cron job missed a fire time while
+/// the process was down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MisfirePolicy {
+    /// Wait for the next regular fire time
+    #[default]
+    Ignore,
+    /// Run once right away, however many fire times were missed
+    FireOnce,
+}
+
+/// Run a job on one instance of the cluster at a time, see `JobKit::set_lock`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SingletonPolicy {
+    /// Longest a run holds the lock, so a dead instance can't keep it forever
+    #[serde(deserialize_with = "format::duration")]
+    pub lock_at_most: Duration,
+    /// Shortest time the lock is held from the start of a run, so instances
+    /// whose clocks are slightly behind don't fire the same tick again
+    #[serde(deserialize_with = "format::duration")]
+    pub lock_at_least: Duration,
+}
+
+impl SingletonPolicy {
+    pub fn new(lock_at_most: Duration) -> Self {
+        Self {
+            lock_at_most,
+            lock_at_least: Duration::ZERO,
+        }
+    }
+
+    /// Keep the lock at least `lock_at_least` after a run starts
+    pub fn lock_at_least(mut self, lock_at_least: Duration) -> Self {
+        self.lock_at_least = lock_at_least;
+        self
+    }
+}
+
+/// Per-job options
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobOptions {
+    pub overlap: OverlapPolicy,
+    pub retry: Option<RetryPolicy>,
+    /// Limit for each attempt, so a run with retries can take up to
+    /// `max_attempts` times as long. A timed out sync attempt can't be
+    /// interrupted, the run waits for it to return before reporting the timeout
+    #[serde(deserialize_with = "format::option_duration")]
+    pub timeout: Option<Duration>,
+    pub misfire: MisfirePolicy,
+    pub timezone: JobTimeZone,
+    /// Stop scheduling after this many runs
+    pub max_runs: Option<u32>,
+    /// Stop scheduling after this time
+    pub end_at: Option<DateTime<Utc>>,
+    /// History records kept, `MAX_HISTORY_RECORDS` by default
+    pub history_capacity: Option<usize>,
+    /// Cluster-wide single instance runs, needs a lock set on the `JobKit`
+    pub singleton: Option<SingletonPolicy>,
+}
+
+impl JobOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the overlap policy
+    pub fn overlap(mut self, overlap: OverlapPolicy) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Set the retry policy
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Set the per-attempt timeout, see `JobOptions::timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the misfire policy
+    pub fn misfire(mut self, misfire: MisfirePolicy) -> Self {
+        self.misfire = misfire;
+        self
+    }
+
+    /// Set the time zone for cron expressions
+    pub fn timezone(mut self, timezone: JobTimeZone) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Limit the number of scheduled runs
+    pub fn max_runs(mut self, max_runs: u32) -> Self {
+        self.max_runs = Some(max_runs);
+        self
+    }
+
+    /// Stop scheduling after `end_at`
+    pub fn end_at(mut self, end_at: DateTime<Utc>) -> Self {
+        self.end_at = Some(end_at);
+        self
+    }
+
+    /// Set the number of history records kept
+    pub fn history_capacity(mut self, capacity: usize) -> Self {
+        self.history_capacity = Some(capacity);
+        self
+    }
+
+    /// Mark the job as a cluster singleton
+    pub fn singleton(mut self, singleton: SingletonPolicy) -> Self {
+        self.singleton = Some(singleton);
+        self
+    }
 }
 
 /// Execution record
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobRecord {
     pub name: String,
     pub timestamp: DateTime<Utc>,
     pub duration: Duration,
     pub result: Result<(), String>,
+    /// Number of attempts made, more than 1 when retried
+    pub attempts: u32,
+    /// Whether the last attempt hit the timeout
+    pub timed_out: bool,
+    /// Whether the run was skipped, e.g. a workflow step whose upstream failed
+    #[serde(default)]
+    pub skipped: bool,
 }
 
+/// Result of a single job run
+pub type JobResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
 /// Job function type
-pub type JobFn = dyn Fn() -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync;
+pub type JobFn = dyn Fn() -> JobResult + Send + Sync;
+
+/// Async job function type
+pub type AsyncJobFn = dyn Fn() -> Pin<Box<dyn Future<Output = JobResult> + Send>> + Send + Sync;
+
+/// Job body: sync functions run on the blocking pool, async ones on the runtime
+#[derive(Clone)]
+pub enum JobTask {
+    Sync(Arc<JobFn>),
+    Async(Arc<AsyncJobFn>),
+}
 
 /// Metadata
 #[derive(Clone)]
@@ -39,128 +384,788 @@ pub struct JobMeta {
     pub id: String,
     pub name: String,
     pub job_type: JobType,
-    pub task: Arc<JobFn>,
+    pub options: JobOptions,
+    pub task: JobTask,
+}
+
+/// Scheduling state of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    /// At least one run is in flight
+    Running,
+    /// Ticks are ignored until resumed
+    Paused,
+    /// Waiting for the next tick
+    Idle,
+    /// The schedule is exhausted, no more runs will fire
+    Finished,
+}
+
+/// Snapshot of a job's state
+#[derive(Debug, Clone)]
+pub struct JobStatus {
+    pub id: String,
+    pub name: String,
+    pub state: JobState,
+    pub last_run: Option<DateTime<Utc>>,
+    pub next_run: Option<DateTime<Utc>>,
+}
+
+/// Result of `JobKit::shutdown`
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownReport {
+    /// Jobs whose in-flight runs finished before the deadline
+    pub completed: Vec<String>,
+    /// Jobs whose in-flight runs were cancelled at the deadline
+    pub cancelled: Vec<String>,
 }
 
 /// Single job entry
 pub struct JobEntry {
     pub meta: JobMeta,
     pub handle: Option<JoinHandle<()>>,
-    pub history: Arc<Mutex<Vec<JobRecord>>>,
+    pub history: Arc<Mutex<JobHistory>>,
+    ctx: Arc<JobContext>,
+    /// Ids of workflow steps owned by this job
+    children: Vec<String>,
+}
+
+/// A cluster lock taken for one run, released when dropped but not before
+/// `release_at` on the clock's monotonic time
+struct HeldLock {
+    lock: Arc<dyn JobLock>,
+    key: String,
+    clock: Arc<dyn Clock>,
+    release_at: Duration,
+}
+
+impl HeldLock {
+    fn unlock(lock: &dyn JobLock, key: &str) {
+        if let Err(e) = lock.unlock(key) {
+            log::error!("[Task Lock Error] name = {}, e = {}", key, e);
+        }
+    }
+}
+
+impl Drop for HeldLock {
+    fn drop(&mut self) {
+        let hold = self.release_at.saturating_sub(self.clock.monotonic());
+        let (lock, key) = (self.lock.clone(), std::mem::take(&mut self.key));
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) if !hold.is_zero() => {
+                let sleep = self.clock.sleep(hold);
+                handle.spawn(async move {
+                    sleep.await;
+                    let _ = tokio::task::spawn_blocking(move || Self::unlock(&*lock, &key)).await;
+                });
+            }
+            _ => Self::unlock(&*lock, &key),
+        }
+    }
+}
+
+/// Parsed form of a `JobType`
+enum Trigger {
+    Interval {
+        delay: Duration,
+        interval: Duration,
+    },
+    Cron {
+        schedule: Box<Schedule>,
+        timezone: JobTimeZone,
+    },
+    Once(DateTime<Utc>),
+}
+
+impl Trigger {
+    /// `now` resolves relative schedules like `JobType::Delay`
+    fn parse(job_type: &JobType, options: &JobOptions, now: DateTime<Utc>) -> Result<Self, String> {
+        if let JobTimeZone::Fixed(secs) = options.timezone {
+            FixedOffset::east_opt(secs).ok_or_else(|| format!("Invalid UTC offset: {}", secs))?;
+        }
+        if let JobType::Interval(interval) | JobType::DelayedInterval { interval, .. } = job_type {
+            if interval.is_zero() {
+                return Err("Interval must be greater than zero".to_string());
+            }
+        }
+        match job_type {
+            JobType::Interval(interval) => Ok(Trigger::Interval {
+                delay: Duration::ZERO,
+                interval: *interval,
+            }),
+            JobType::DelayedInterval { delay, interval } => Ok(Trigger::Interval {
+                delay: *delay,
+                interval: *interval,
+            }),
+            JobType::Cron(expr) => Schedule::from_str(expr)
+                .map(|schedule| Trigger::Cron {
+                    schedule: Box::new(schedule),
+                    timezone: options.timezone,
+                })
+                .map_err(|e| e.to_string()),
+            JobType::At(at) => Ok(Trigger::Once(*at)),
+            JobType::Delay(delay) => chrono::Duration::from_std(*delay)
+                .map(|delay| Trigger::Once(now + delay))
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+type Listeners = Arc<RwLock<Vec<Arc<dyn JobListener>>>>;
+
+type SharedLock = Arc<RwLock<Option<Arc<dyn JobLock>>>>;
+
+/// Shared state of a scheduled job, handed to its runner
+struct JobContext {
+    id: String,
+    name: String,
+    job_type: JobType,
+    created_at: DateTime<Utc>,
+    task: JobTask,
+    history: Arc<Mutex<JobHistory>>,
+    options: JobOptions,
+    running: AtomicUsize,
+    paused: AtomicBool,
+    run_lock: tokio::sync::Mutex<()>,
+    last_run: Mutex<Option<DateTime<Utc>>>,
+    next_run: Mutex<Option<DateTime<Utc>>>,
+    /// Scheduled runs so far, checked against `max_runs`
+    fired: AtomicU32,
+    finished: AtomicBool,
+    /// Workflow roots only wait for their steps and take no permit
+    holds_permit: bool,
+    semaphore: Arc<Semaphore>,
+    store: Option<Arc<dyn JobStore>>,
+    /// Records in the stored history file, checked against twice the capacity
+    stored_records: AtomicUsize,
+    /// Keeps the store writes of this job in order
+    store_io: Arc<Mutex<()>>,
+    listeners: Listeners,
+    lock: SharedLock,
+    clock: Arc<dyn Clock>,
+    shutdown: watch::Receiver<bool>,
+    /// Runs spawned outside the runner, aborted when a shutdown times out
+    tasks: Mutex<Vec<AbortHandle>>,
+}
+
+/// A workflow step and its edges, indices point into the plan
+struct DagStep {
+    ctx: Arc<JobContext>,
+    deps: Vec<usize>,
+    dependents: Vec<usize>,
+}
+
+impl JobContext {
+    /// Whether the schedule allows no run at `next`
+    fn exhausted(&self, next: DateTime<Utc>) -> bool {
+        self.finished.load(Ordering::SeqCst)
+            || self
+                .options
+                .max_runs
+                .is_some_and(|max| self.fired.load(Ordering::SeqCst) >= max)
+            || self.options.end_at.is_some_and(|end| next > end)
+    }
+
+    fn is_shutting_down(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+
+    /// Resolves once `JobKit::shutdown` has been called
+    async fn shutdown_signal(&self) {
+        let mut shutdown = self.shutdown.clone();
+        let _ = shutdown.wait_for(|down| *down).await;
+    }
+
+    fn track<T>(&self, handle: JoinHandle<T>) {
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|task| !task.is_finished());
+        tasks.push(handle.abort_handle());
+    }
+
+    fn finish(&self) {
+        self.finished.store(true, Ordering::SeqCst);
+        *self.next_run.lock().unwrap() = None;
+    }
+
+    fn emit(&self, event: impl Fn(&dyn JobListener)) {
+        for listener in self.listeners.read().unwrap().iter() {
+            event(listener.as_ref());
+        }
+    }
+
+    fn to_stored(&self) -> StoredJob {
+        StoredJob {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            job_type: self.job_type.clone(),
+            options: self.options.clone(),
+            created_at: self.created_at,
+            last_run: *self.last_run.lock().unwrap(),
+        }
+    }
+
+    /// Append the record on a blocking thread, store errors never fail a run.
+    /// The definition is only saved when it is added, `last_run` is read back
+    /// from the history. Once the history file holds twice the capacity it is
+    /// trimmed back, and the definition saved so `last_run` survives the trim.
+    async fn persist(&self, record: &JobRecord) {
+        let Some(store) = self.store.clone() else {
+            return;
+        };
+        let capacity = self.history.lock().unwrap().capacity();
+        let stored = self.stored_records.fetch_add(1, Ordering::SeqCst) + 1;
+        let compact = (stored > capacity.max(1) * 2).then(|| {
+            self.stored_records.store(capacity, Ordering::SeqCst);
+            self.to_stored()
+        });
+        let (store_io, id, record) = (self.store_io.clone(), self.id.clone(), record.clone());
+        let written = tokio::task::spawn_blocking(move || {
+            let _guard = store_io.lock().unwrap_or_else(PoisonError::into_inner);
+            store
+                .append_record(&id, &record)
+                .and_then(|_| match compact {
+                    Some(job) => Self::compact(store.as_ref(), &job, capacity),
+                    None => Ok(()),
+                })
+        })
+        .await;
+        let written = written.unwrap_or_else(|e| Err(io::Error::other(e)));
+        if let Err(e) = written {
+            log::error!("[Task Store Error] name = {}, e = {}", self.name, e);
+        }
+    }
+
+    fn compact(store: &dyn JobStore, job: &StoredJob, capacity: usize) -> io::Result<()> {
+        let mut records = store.load_history(&job.id)?;
+        if records.len() > capacity {
+            records.drain(..records.len() - capacity);
+            store.save_history(&job.id, &records)?;
+        }
+        store.save_job(job)
+    }
 }
 
 /// Job manager
 pub struct JobKit {
     jobs: Arc<Mutex<HashMap<String, JobEntry>>>,
     name_map: Arc<Mutex<HashMap<String, String>>>,
-    _max_concurrent_tasks: usize,
+    semaphore: Arc<Semaphore>,
+    max_concurrent_tasks: usize,
+    store: Option<Arc<dyn JobStore>>,
+    listeners: Listeners,
+    lock: SharedLock,
+    clock: Arc<dyn Clock>,
+    shutdown: watch::Sender<bool>,
 }
 
 impl JobKit {
-    pub fn new(_max_concurrent_tasks: usize) -> Self {
+    /// Create a job manager running at most `max_concurrent_tasks` runs at once
+    /// across all jobs, `0` means unbounded
+    pub fn new(max_concurrent_tasks: usize) -> Self {
+        let permits = match max_concurrent_tasks {
+            0 => Semaphore::MAX_PERMITS,
+            n => n,
+        };
         Self {
             jobs: Arc::new(Mutex::new(HashMap::new())),
             name_map: Arc::new(Mutex::new(HashMap::new())),
-            _max_concurrent_tasks,
+            semaphore: Arc::new(Semaphore::new(permits)),
+            max_concurrent_tasks,
+            store: None,
+            listeners: Arc::new(RwLock::new(vec![Arc::new(LogListener)])),
+            lock: Arc::new(RwLock::new(None)),
+            clock: clockkit::system(),
+            shutdown: watch::Sender::new(false),
         }
     }
 
+    /// Register a lifecycle listener, applies to existing and future jobs
+    pub fn add_listener(&self, listener: Arc<dyn JobListener>) {
+        self.listeners.write().unwrap().push(listener);
+    }
+
+    /// Remove all listeners, including the default `LogListener`
+    pub fn clear_listeners(&self) {
+        self.listeners.write().unwrap().clear();
+    }
+
+    /// Set the cluster lock used by singleton jobs, applies to existing and
+    /// future jobs. Without a lock singleton jobs run like any other job.
+    pub fn set_lock(&self, lock: Arc<dyn JobLock>) {
+        *self.lock.write().unwrap() = Some(lock);
+    }
+
+    /// Use `clock` for schedules, retry delays and timeouts instead of the
+    /// system clock, set it before adding jobs
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Create a job manager that persists definitions, last runs and history.
+    /// Jobs added under a stored name get back their id and history.
+    pub fn with_store(max_concurrent_tasks: usize, store: Arc<dyn JobStore>) -> Self {
+        let mut kit = Self::new(max_concurrent_tasks);
+        kit.store = Some(store);
+        kit
+    }
+
+    /// Max number of concurrent runs, `0` means unbounded
+    pub fn max_concurrent_tasks(&self) -> usize {
+        self.max_concurrent_tasks
+    }
+
     /// Add a new job
     pub fn add_job(
         &self,
         name: &str,
         job_type: JobType,
-        task: impl Fn() -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync + 'static,
+        task: impl Fn() -> JobResult + Send + Sync + 'static,
+    ) -> Result<String, String> {
+        self.add_job_with_options(name, job_type, JobOptions::default(), task)
+    }
+
+    /// Add a new job with options
+    pub fn add_job_with_options(
+        &self,
+        name: &str,
+        job_type: JobType,
+        options: JobOptions,
+        task: impl Fn() -> JobResult + Send + Sync + 'static,
+    ) -> Result<String, String> {
+        self.add_task(name, job_type, options, JobTask::Sync(Arc::new(task)))
+    }
+
+    /// Add a new async job, it runs directly on the tokio runtime
+    pub fn add_async_job<F, Fut>(
+        &self,
+        name: &str,
+        job_type: JobType,
+        task: F,
+    ) -> Result<String, String>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = JobResult> + Send + 'static,
+    {
+        self.add_async_job_with_options(name, job_type, JobOptions::default(), task)
+    }
+
+    /// Add a new async job with options
+    pub fn add_async_job_with_options<F, Fut>(
+        &self,
+        name: &str,
+        job_type: JobType,
+        options: JobOptions,
+        task: F,
+    ) -> Result<String, String>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = JobResult> + Send + 'static,
+    {
+        let task: Arc<AsyncJobFn> = Arc::new(move || Box::pin(task()));
+        self.add_task(name, job_type, options, JobTask::Async(task))
+    }
+
+    /// Add a workflow: the DAG runs when `job_type` fires, each step is also
+    /// listed as a job named `workflow/step` with its own history
+    pub fn add_workflow(
+        &self,
+        mut workflow: Workflow,
+        job_type: JobType,
+        options: JobOptions,
+    ) -> Result<String, String> {
+        workflow.validate()?;
+        let step_name = |step: &str| format!("{}/{}", workflow.name, step);
+        self.check_name(&workflow.name)?;
+        for step in &workflow.steps {
+            self.check_name(&step_name(&step.name))?;
+        }
+        let trigger = Trigger::parse(&job_type, &options, self.clock.now())?;
+
+        let mut dependents = vec![Vec::new(); workflow.steps.len()];
+        for (i, step) in workflow.steps.iter().enumerate() {
+            for &dep in &step.deps {
+                dependents[dep].push(i);
+            }
+        }
+
+        let mut steps = Vec::with_capacity(workflow.steps.len());
+        let mut entries = Vec::with_capacity(workflow.steps.len());
+        for (step, dependents) in workflow.steps.iter().zip(dependents) {
+            let (ctx, meta, _) = self.new_context(
+                &step_name(&step.name),
+                job_type.clone(),
+                step.options.clone(),
+                step.task.clone(),
+                true,
+            )?;
+            steps.push(DagStep {
+                ctx: ctx.clone(),
+                deps: step.deps.clone(),
+                dependents,
+            });
+            entries.push((meta, ctx));
+        }
+
+        let plan = Arc::new(steps);
+        let task: Arc<AsyncJobFn> = Arc::new(move || Box::pin(Self::run_dag(plan.clone())));
+        let (ctx, meta, _) = self.new_context(
+            &workflow.name,
+            job_type,
+            options,
+            JobTask::Async(task),
+            false,
+        )?;
+        let handle = Self::spawn_runner(&ctx, trigger);
+
+        let children = entries
+            .into_iter()
+            .map(|(meta, ctx)| self.insert_entry(meta, None, ctx, Vec::new()))
+            .collect();
+        Ok(self.insert_entry(meta, Some(handle), ctx, children))
+    }
+
+    pub(crate) fn add_task(
+        &self,
+        name: &str,
+        job_type: JobType,
+        options: JobOptions,
+        task: JobTask,
     ) -> Result<String, String> {
-        let name = name.to_string();
-        if self.name_map.lock().unwrap().contains_key(&name) {
+        self.check_name(name)?;
+        let trigger = Trigger::parse(&job_type, &options, self.clock.now())?;
+        let (ctx, meta, restored) = self.new_context(name, job_type, options, task, true)?;
+
+        let last_run = *ctx.last_run.lock().unwrap();
+        let misfired = match (&trigger, restored) {
+            (Trigger::Cron { schedule, timezone }, true) => {
+                let since = last_run.unwrap_or(ctx.created_at);
+                timezone
+                    .next_fire(schedule, since)
+                    .is_some_and(|t| t <= ctx.clock.now())
+            }
+            // a restored one-shot job that already ran stays done
+            (Trigger::Once(_), true) => {
+                if last_run.is_some() {
+                    ctx.finish();
+                }
+                false
+            }
+            _ => false,
+        };
+
+        let handle = Self::spawn_runner(&ctx, trigger);
+
+        if misfired {
+            match ctx.options.misfire {
+                MisfirePolicy::FireOnce => {
+                    log::warn!("[Task Misfire] name = {}, firing once now", name);
+                    let run = ctx.clone();
+                    ctx.track(tokio::spawn(async move { Self::dispatch(&run).await }));
+                }
+                MisfirePolicy::Ignore => {
+                    log::warn!("[Task Misfire] name = {}, ignored", name);
+                }
+            }
+        }
+
+        Ok(self.insert_entry(meta, Some(handle), ctx, Vec::new()))
+    }
+
+    /// Check a schedule and its options without adding a job
+    pub(crate) fn check_definition(
+        &self,
+        job_type: &JobType,
+        options: &JobOptions,
+    ) -> Result<(), String> {
+        Trigger::parse(job_type, options, self.clock.now()).map(|_| ())
+    }
+
+    pub(crate) fn check_name(&self, name: &str) -> Result<(), String> {
+        if *self.shutdown.borrow() {
+            return Err("JobKit is shut down".to_string());
+        }
+        if self.name_map.lock().unwrap().contains_key(name) {
             return Err("Job name already exists".to_string());
         }
+        Ok(())
+    }
+
+    /// Build the context of a job, restoring id, last run and history from the
+    /// store. Returns whether a stored definition was found.
+    fn new_context(
+        &self,
+        name: &str,
+        job_type: JobType,
+        options: JobOptions,
+        task: JobTask,
+        holds_permit: bool,
+    ) -> Result<(Arc<JobContext>, JobMeta, bool), String> {
+        let stored = match &self.store {
+            Some(store) => store
+                .load_jobs()
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .find(|j| j.name == name),
+            None => None,
+        };
+        let id = stored
+            .as_ref()
+            .map(|j| j.id.clone())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let created_at = stored
+            .as_ref()
+            .map(|j| j.created_at)
+            .unwrap_or_else(|| self.clock.now());
+        let mut last_run = stored.as_ref().and_then(|j| j.last_run);
+
+        let capacity = options.history_capacity.unwrap_or(MAX_HISTORY_RECORDS);
+        let mut history = JobHistory::new(capacity);
+        if let Some(store) = &self.store {
+            let mut records = store.load_history(&id).map_err(|e| e.to_string())?;
+            let recorded = records.iter().filter(|r| !r.skipped).map(|r| r.timestamp);
+            last_run = last_run.into_iter().chain(recorded).max();
+            if records.len() > capacity {
+                records.drain(..records.len() - capacity);
+                store
+                    .save_history(&id, &records)
+                    .map_err(|e| e.to_string())?;
+            }
+            records.into_iter().for_each(|record| history.push(record));
+        }
+        let stored_records = history.len();
 
-        let id = Uuid::new_v4().to_string();
         let meta = JobMeta {
             id: id.clone(),
-            name: name.clone(),
+            name: name.to_string(),
             job_type: job_type.clone(),
-            task: Arc::new(task),
+            options: options.clone(),
+            task,
         };
 
-        let history = Arc::new(Mutex::new(Vec::new()));
-        let task_clone = meta.task.clone();
-        let history_clone = history.clone();
-        let task_name = name.clone();
+        let ctx = Arc::new(JobContext {
+            id,
+            name: name.to_string(),
+            job_type,
+            created_at,
+            task: meta.task.clone(),
+            history: Arc::new(Mutex::new(history)),
+            options,
+            running: AtomicUsize::new(0),
+            paused: AtomicBool::new(false),
+            run_lock: tokio::sync::Mutex::new(()),
+            last_run: Mutex::new(last_run),
+            next_run: Mutex::new(None),
+            fired: AtomicU32::new(0),
+            finished: AtomicBool::new(false),
+            holds_permit,
+            semaphore: self.semaphore.clone(),
+            clock: self.clock.clone(),
+            store: self.store.clone(),
+            stored_records: AtomicUsize::new(stored_records),
+            store_io: Arc::new(Mutex::new(())),
+            listeners: self.listeners.clone(),
+            lock: self.lock.clone(),
+            shutdown: self.shutdown.subscribe(),
+            tasks: Mutex::new(Vec::new()),
+        });
 
-        let handle = match job_type {
-            JobType::Interval(interval) => tokio::spawn(Self::interval_runner(
-                task_name,
-                task_clone,
-                history_clone,
-                interval,
-            )),
-            JobType::Cron(expr) => {
-                let schedule = Schedule::from_str(&expr).map_err(|e| e.to_string())?;
-                tokio::spawn(Self::cron_runner(
-                    task_name,
-                    task_clone,
-                    history_clone,
-                    schedule,
-                ))
+        if let Some(store) = &self.store {
+            store
+                .save_job(&ctx.to_stored())
+                .map_err(|e| e.to_string())?;
+        }
+        Ok((ctx, meta, stored.is_some()))
+    }
+
+    fn spawn_runner(ctx: &Arc<JobContext>, trigger: Trigger) -> JoinHandle<()> {
+        match trigger {
+            Trigger::Interval { delay, interval } => {
+                tokio::spawn(Self::interval_runner(ctx.clone(), delay, interval))
             }
-        };
+            Trigger::Cron { schedule, timezone } => {
+                tokio::spawn(Self::cron_runner(ctx.clone(), *schedule, timezone))
+            }
+            Trigger::Once(at) => tokio::spawn(Self::once_runner(ctx.clone(), at)),
+        }
+    }
 
+    fn insert_entry(
+        &self,
+        meta: JobMeta,
+        handle: Option<JoinHandle<()>>,
+        ctx: Arc<JobContext>,
+        children: Vec<String>,
+    ) -> String {
+        let id = meta.id.clone();
+        let name = meta.name.clone();
         let entry = JobEntry {
             meta,
-            handle: Some(handle),
-            history,
+            handle,
+            history: ctx.history.clone(),
+            ctx,
+            children,
         };
-
         self.jobs.lock().unwrap().insert(id.clone(), entry);
         self.name_map.lock().unwrap().insert(name, id.clone());
-        Ok(id)
+        id
     }
 
-    async fn interval_runner(
-        task_name: String,
-        task: Arc<JobFn>,
-        history: Arc<Mutex<Vec<JobRecord>>>,
-        interval: Duration,
-    ) {
-        let mut ticker = time::interval(interval);
+    async fn interval_runner(ctx: Arc<JobContext>, delay: Duration, interval: Duration) {
+        // scheduled on monotonic time, so wall clock steps neither skip nor
+        // burst runs; missed ticks still fire right away, like a tokio interval
+        let start = ctx.clock.monotonic();
+        let mut offset = delay;
         loop {
-            ticker.tick().await;
-            Self::execute_task(&task_name, &task, &history);
+            let wait = (start + offset).saturating_sub(ctx.clock.monotonic());
+            let next = ctx.clock.now() + chrono::Duration::from_std(wait).unwrap_or_default();
+            if ctx.exhausted(next) {
+                break;
+            }
+            *ctx.next_run.lock().unwrap() = Some(next);
+            tokio::select! {
+                _ = ctx.clock.sleep(wait) => {}
+                _ = ctx.shutdown_signal() => break,
+            }
+            offset += interval;
+            Self::tick(&ctx).await;
+        }
+        ctx.finish();
+    }
+
+    async fn cron_runner(ctx: Arc<JobContext>, schedule: Schedule, timezone: JobTimeZone) {
+        let mut after = ctx.clock.now();
+        while let Some(next) = timezone.next_fire(&schedule, after) {
+            if ctx.exhausted(next) {
+                break;
+            }
+            *ctx.next_run.lock().unwrap() = Some(next);
+            tokio::select! {
+                _ = ctx.clock.sleep_until(next) => {}
+                _ = ctx.shutdown_signal() => break,
+            }
+            Self::tick(&ctx).await;
+            after = next;
+        }
+        ctx.finish();
+    }
+
+    async fn once_runner(ctx: Arc<JobContext>, at: DateTime<Utc>) {
+        if !ctx.exhausted(at) {
+            *ctx.next_run.lock().unwrap() = Some(at);
+            tokio::select! {
+                _ = ctx.clock.sleep_until(at) => Self::tick(&ctx).await,
+                _ = ctx.shutdown_signal() => {}
+            }
+        }
+        ctx.finish();
+    }
+
+    /// Handle a scheduled tick, paused jobs ignore it
+    async fn tick(ctx: &Arc<JobContext>) {
+        if ctx.paused.load(Ordering::SeqCst) || ctx.is_shutting_down() {
+            return;
+        }
+        ctx.fired.fetch_add(1, Ordering::SeqCst);
+        Self::dispatch(ctx).await;
+    }
+
+    /// Start a run according to the job's overlap policy
+    async fn dispatch(ctx: &Arc<JobContext>) {
+        match ctx.options.overlap {
+            OverlapPolicy::Queue => {
+                let _guard = ctx.run_lock.lock().await;
+                ctx.running.fetch_add(1, Ordering::SeqCst);
+                Self::run_locked(ctx.clone()).await;
+            }
+            OverlapPolicy::Skip => {
+                if ctx
+                    .running
+                    .compare_exchange(0, 1, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_err()
+                {
+                    ctx.emit(|l| l.on_skip(&ctx.name, "previous run still in progress"));
+                    return;
+                }
+                ctx.track(tokio::spawn(Self::run_locked(ctx.clone())));
+            }
+            OverlapPolicy::Allow => {
+                ctx.running.fetch_add(1, Ordering::SeqCst);
+                ctx.track(tokio::spawn(Self::run_locked(ctx.clone())));
+            }
         }
     }
 
-    async fn cron_runner(
-        task_name: String,
-        task: Arc<JobFn>,
-        history: Arc<Mutex<Vec<JobRecord>>>,
-        schedule: Schedule,
-    ) {
-        let mut upcoming = schedule.upcoming(Utc);
-        while let Some(next) = upcoming.next() {
-            let now = Utc::now();
-            let delay = (next - now).to_std().unwrap_or(Duration::ZERO);
-            time::sleep(delay).await;
-            Self::execute_task(&task_name, &task, &history);
+    /// Run the task holding the cluster lock if the job is a singleton, the run
+    /// is skipped when another instance holds it
+    async fn run_locked(ctx: Arc<JobContext>) -> bool {
+        let lock = ctx.lock.read().unwrap().clone();
+        let (Some(policy), Some(lock)) = (ctx.options.singleton, lock) else {
+            return Self::execute_task(ctx).await;
+        };
+
+        let start = ctx.clock.monotonic();
+        let key = ctx.name.clone();
+        let acquired = {
+            let (lock, key) = (lock.clone(), key.clone());
+            match tokio::task::spawn_blocking(move || lock.try_lock(&key, policy.lock_at_most))
+                .await
+            {
+                Ok(acquired) => acquired,
+                Err(e) => Err(std::io::Error::other(e)),
+            }
+        };
+        let reason = match acquired {
+            Ok(true) => None,
+            Ok(false) => Some("locked by another instance"),
+            Err(e) => {
+                log::error!("[Task Lock Error] name = {}, e = {}", ctx.name, e);
+                Some("lock unavailable")
+            }
+        };
+        if let Some(reason) = reason {
+            ctx.running.fetch_sub(1, Ordering::SeqCst);
+            ctx.emit(|l| l.on_skip(&ctx.name, reason));
+            return false;
         }
+
+        // released on drop, so a run aborted by `stop` or a shutdown deadline
+        // gives the lock back too
+        let _held = HeldLock {
+            lock,
+            key,
+            clock: ctx.clock.clone(),
+            release_at: start + policy.lock_at_least,
+        };
+        Self::execute_task(ctx.clone()).await
     }
 
-    fn execute_task(task_name: &str, task: &Arc<JobFn>, history: &Arc<Mutex<Vec<JobRecord>>>) {
+    /// Run the task with retries, the caller must have counted it in `ctx.running`.
+    /// Returns whether the run succeeded.
+    async fn execute_task(ctx: Arc<JobContext>) -> bool {
         let start = Instant::now();
-        let ts = Utc::now();
-        let task = Arc::clone(task);
+        let ts = ctx.clock.now();
+        *ctx.last_run.lock().unwrap() = Some(ts);
+        let task_name = ctx.name.as_str();
+        ctx.emit(|l| l.on_start(task_name, ts));
 
-        let result = std::thread::spawn(move || {
-            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| task()))
-        })
-        .join()
-        .map_err(|_| "Task thread panicked".to_string())
-        .and_then(|r| {
-            r.map_err(|_| "Task panicked".to_string())
-                .and_then(|res| res.map_err(|e| e.to_string()))
-        });
+        let mut attempts = 1;
+        let (result, timed_out) = loop {
+            let (result, timed_out) = Self::run_once(&ctx).await;
+            let retry = match (&result, ctx.options.retry) {
+                (Err(_), Some(retry))
+                    if attempts < retry.max_attempts && !ctx.is_shutting_down() =>
+                {
+                    retry
+                }
+                _ => break (result, timed_out),
+            };
+            let delay = retry.delay(attempts);
+            let error = result.unwrap_err();
+            ctx.emit(|l| l.on_retry(task_name, attempts, delay, &error));
+            ctx.clock.sleep(delay).await;
+            attempts += 1;
+        };
 
         let duration = start.elapsed();
         let record = JobRecord {
@@ -168,27 +1173,186 @@ impl JobKit {
             timestamp: ts,
             duration,
             result,
+            attempts,
+            timed_out,
+            skipped: false,
+        };
+
+        if record.result.is_ok() {
+            ctx.emit(|l| l.on_success(&record));
+        } else {
+            ctx.emit(|l| l.on_failure(&record));
+        }
+
+        let ok = record.result.is_ok();
+        ctx.persist(&record).await;
+        ctx.history.lock().unwrap().push(record);
+        ctx.running.fetch_sub(1, Ordering::SeqCst);
+        ok
+    }
+
+    /// Run a workflow: steps start once all their upstream steps succeeded, a
+    /// failed step marks every downstream step as skipped
+    async fn run_dag(plan: Arc<Vec<DagStep>>) -> JobResult {
+        let mut pending: Vec<usize> = plan.iter().map(|step| step.deps.len()).collect();
+        let mut skipped = vec![false; plan.len()];
+        let mut failed = Vec::new();
+        let mut set = tokio::task::JoinSet::new();
+
+        let spawn = |set: &mut tokio::task::JoinSet<(usize, bool)>, i: usize| {
+            let ctx = plan[i].ctx.clone();
+            ctx.running.fetch_add(1, Ordering::SeqCst);
+            set.spawn(async move { (i, Self::execute_task(ctx).await) });
+        };
+
+        for i in (0..plan.len()).filter(|&i| pending[i] == 0) {
+            spawn(&mut set, i);
+        }
+
+        while let Some(joined) = set.join_next().await {
+            let (i, ok) = joined.map_err(|e| e.to_string())?;
+            if ok {
+                for &next in &plan[i].dependents {
+                    pending[next] -= 1;
+                    if pending[next] == 0 && !skipped[next] {
+                        spawn(&mut set, next);
+                    }
+                }
+                continue;
+            }
+
+            failed.push(plan[i].ctx.name.clone());
+            let mut stack = plan[i].dependents.clone();
+            while let Some(next) = stack.pop() {
+                if skipped[next] {
+                    continue;
+                }
+                skipped[next] = true;
+                Self::record_skip(&plan[next].ctx, &plan[i].ctx.name).await;
+                stack.extend(plan[next].dependents.iter().copied());
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Workflow steps failed: {}", failed.join(", ")).into())
+        }
+    }
+
+    async fn record_skip(ctx: &JobContext, upstream: &str) {
+        let ts = ctx.clock.now();
+        let record = JobRecord {
+            name: ctx.name.clone(),
+            timestamp: ts,
+            duration: Duration::ZERO,
+            result: Err(format!("Skipped, upstream {} failed", upstream)),
+            attempts: 0,
+            timed_out: false,
+            skipped: true,
+        };
+        let reason = format!("upstream {} failed", upstream);
+        ctx.emit(|l| l.on_skip(&ctx.name, &reason));
+        ctx.persist(&record).await;
+        ctx.history.lock().unwrap().push(record);
+    }
+
+    /// Run a single attempt under a concurrency permit, returns the result and
+    /// whether it timed out. A timed out async attempt is aborted, a timed out
+    /// sync attempt is waited for, and its blocking thread keeps the permit
+    /// until it returns even if the run is aborted.
+    async fn run_once(ctx: &Arc<JobContext>) -> (Result<(), String>, bool) {
+        let permit = match ctx.holds_permit {
+            true => Some(ctx.semaphore.clone().acquire_owned().await),
+            false => None,
+        };
+        let timeout = ctx.options.timeout;
+
+        let result = match &ctx.task {
+            JobTask::Sync(task) => {
+                let task = Arc::clone(task);
+                let handle = tokio::task::spawn_blocking(move || {
+                    let _permit = permit;
+                    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| task()))
+                });
+                Self::join_blocking(handle, timeout, ctx.clock.as_ref())
+                    .await
+                    .map(|joined| {
+                        joined
+                            .map_err(|_| "Task thread panicked".to_string())
+                            .and_then(|r| {
+                                r.map_err(|_| "Task panicked".to_string())
+                                    .and_then(|res| res.map_err(|e| e.to_string()))
+                            })
+                    })
+            }
+            JobTask::Async(task) => {
+                let _permit = permit;
+                let task = Arc::clone(task);
+                let handle = tokio::spawn(async move { task().await });
+                Self::join(handle, timeout, ctx.clock.as_ref())
+                    .await
+                    .map(|joined| {
+                        joined
+                            .map_err(|_| "Task panicked".to_string())
+                            .and_then(|res| res.map_err(|e| e.to_string()))
+                    })
+            }
         };
 
-        if let Err(ref e) = record.result {
-            eprintln!(
-                "[Task Error] name = {}, time = {}, e = {}",
-                task_name, ts, e
-            );
+        match result {
+            Some(result) => (result, false),
+            None => (
+                Err(format!(
+                    "Task timed out after {:?}",
+                    timeout.unwrap_or_default()
+                )),
+                true,
+            ),
+        }
+    }
+
+    /// Wait for a spawned attempt, `None` means it timed out. The attempt is
+    /// aborted when it times out or the waiting run itself is cancelled.
+    async fn join<T>(
+        mut handle: JoinHandle<T>,
+        timeout: Option<Duration>,
+        clock: &dyn Clock,
+    ) -> Option<Result<T, JoinError>> {
+        struct AbortOnDrop(AbortHandle);
+        impl Drop for AbortOnDrop {
+            fn drop(&mut self) {
+                self.0.abort();
+            }
         }
+        let _guard = AbortOnDrop(handle.abort_handle());
 
-        Self::push_history(history, record);
-        println!(
-            "[Task Success] name = {}, time = {}, duration = {:?}",
-            task_name, ts, duration
-        );
+        match timeout {
+            Some(timeout) => tokio::select! {
+                joined = &mut handle => Some(joined),
+                _ = clock.sleep(timeout) => None,
+            },
+            None => Some(handle.await),
+        }
     }
 
-    fn push_history(history: &Arc<Mutex<Vec<JobRecord>>>, record: JobRecord) {
-        let mut h = history.lock().unwrap();
-        h.push(record);
-        if h.len() > MAX_HISTORY_RECORDS {
-            h.remove(0);
+    /// Wait for a blocking attempt, `None` means it timed out. A blocking
+    /// thread can't be aborted, so a timed out attempt is still waited for and
+    /// the run keeps its `running` slot until the thread returns.
+    async fn join_blocking<T>(
+        mut handle: JoinHandle<T>,
+        timeout: Option<Duration>,
+        clock: &dyn Clock,
+    ) -> Option<Result<T, JoinError>> {
+        let Some(timeout) = timeout else {
+            return Some(handle.await);
+        };
+        tokio::select! {
+            joined = &mut handle => Some(joined),
+            _ = clock.sleep(timeout) => {
+                let _ = handle.await;
+                None
+            }
         }
     }
 
@@ -196,17 +1360,47 @@ impl JobKit {
     pub fn stop(&self, id_or_name: &str) -> bool {
         let id = self.resolve_id(id_or_name);
         if let Some(id) = id {
-            if let Some(mut job) = self.jobs.lock().unwrap().remove(&id) {
+            let job = self.jobs.lock().unwrap().remove(&id);
+            if let Some(mut job) = job {
                 if let Some(handle) = job.handle.take() {
                     handle.abort();
                 }
                 self.name_map.lock().unwrap().retain(|_, v| v != &id);
+                for child in &job.children {
+                    self.stop(child);
+                }
                 return true;
             }
         }
         false
     }
 
+    /// Stop a job and delete it from the store, `stop` keeps the stored state
+    pub fn remove(&self, id_or_name: &str) -> bool {
+        let Some(id) = self.resolve_id(id_or_name) else {
+            return false;
+        };
+        let mut ids = self
+            .jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|job| job.children.clone())
+            .unwrap_or_default();
+        if !self.stop(&id) {
+            return false;
+        }
+        ids.push(id);
+        if let Some(store) = &self.store {
+            for id in ids {
+                if let Err(e) = store.remove_job(&id) {
+                    log::error!("[Task Store Error] id = {}, e = {}", id, e);
+                }
+            }
+        }
+        true
+    }
+
     /// Stop all jobs
     pub fn stop_all(&self) {
         let mut jobs = self.jobs.lock().unwrap();
@@ -218,6 +1412,124 @@ impl JobKit {
         self.name_map.lock().unwrap().clear();
     }
 
+    /// Stop scheduling new runs, wait up to `timeout` for in-flight runs to
+    /// finish, then cancel the rest and remove all jobs. Sync runs that are
+    /// cancelled keep their blocking thread until the function returns.
+    pub async fn shutdown(&self, timeout: Duration) -> ShutdownReport {
+        self.shutdown.send_replace(true);
+        let entries: Vec<JobEntry> = self.jobs.lock().unwrap().drain().map(|(_, e)| e).collect();
+        self.name_map.lock().unwrap().clear();
+
+        let busy = |entry: &JobEntry| entry.ctx.running.load(Ordering::SeqCst) > 0;
+        let in_flight: Vec<String> = entries
+            .iter()
+            .filter(|e| busy(e))
+            .map(|e| e.meta.name.clone())
+            .collect();
+
+        let deadline = time::Instant::now() + timeout;
+        while entries.iter().any(busy) && time::Instant::now() < deadline {
+            time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let mut report = ShutdownReport::default();
+        for entry in entries {
+            let cancelled = busy(&entry);
+            if let Some(handle) = entry.handle {
+                handle.abort();
+            }
+            for task in entry.ctx.tasks.lock().unwrap().drain(..) {
+                task.abort();
+            }
+            if cancelled {
+                log::warn!("[Task Cancelled] name = {}", entry.meta.name);
+                report.cancelled.push(entry.meta.name);
+            } else if in_flight.contains(&entry.meta.name) {
+                report.completed.push(entry.meta.name);
+            }
+        }
+        report
+    }
+
+    /// Wait for ctrl-c or SIGTERM, then `shutdown`
+    pub async fn shutdown_on_signal(&self, timeout: Duration) -> ShutdownReport {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            match signal(SignalKind::terminate()) {
+                Ok(mut sigterm) => {
+                    tokio::select! {
+                        _ = tokio::signal::ctrl_c() => {}
+                        _ = sigterm.recv() => {}
+                    }
+                }
+                Err(_) => {
+                    let _ = tokio::signal::ctrl_c().await;
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+        self.shutdown(timeout).await
+    }
+
+    /// Pause a job by id or name, its history and schedule are kept
+    pub fn pause(&self, id_or_name: &str) -> bool {
+        self.with_ctx(id_or_name, |ctx| ctx.paused.store(true, Ordering::SeqCst))
+            .is_some()
+    }
+
+    /// Resume a paused job by id or name
+    pub fn resume(&self, id_or_name: &str) -> bool {
+        self.with_ctx(id_or_name, |ctx| ctx.paused.store(false, Ordering::SeqCst))
+            .is_some()
+    }
+
+    /// Run a job once out of schedule, also works while paused
+    pub fn trigger_now(&self, id_or_name: &str) -> bool {
+        if *self.shutdown.borrow() {
+            return false;
+        }
+        self.with_ctx(id_or_name, |ctx| {
+            let run = ctx.clone();
+            ctx.track(tokio::spawn(async move { Self::dispatch(&run).await }));
+        })
+        .is_some()
+    }
+
+    /// Get job status by id or name
+    pub fn status(&self, id_or_name: &str) -> Option<JobStatus> {
+        let id = self.resolve_id(id_or_name)?;
+        let jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get(&id)?;
+        let ctx = &entry.ctx;
+        let paused = ctx.paused.load(Ordering::SeqCst);
+        let state = if paused {
+            JobState::Paused
+        } else if ctx.running.load(Ordering::SeqCst) > 0 {
+            JobState::Running
+        } else if ctx.finished.load(Ordering::SeqCst) {
+            JobState::Finished
+        } else {
+            JobState::Idle
+        };
+        let last_run = *ctx.last_run.lock().unwrap();
+        let next_run = if paused {
+            None
+        } else {
+            *ctx.next_run.lock().unwrap()
+        };
+        Some(JobStatus {
+            id: entry.meta.id.clone(),
+            name: entry.meta.name.clone(),
+            state,
+            last_run,
+            next_run,
+        })
+    }
+
     /// Get job history by id or name
     pub fn get_history(&self, id_or_name: &str) -> Option<Vec<JobRecord>> {
         let id = self.resolve_id(id_or_name)?;
@@ -225,7 +1537,24 @@ impl JobKit {
             .lock()
             .unwrap()
             .get(&id)
-            .map(|entry| entry.history.lock().unwrap().clone())
+            .map(|entry| entry.history.lock().unwrap().to_vec())
+    }
+
+    /// Query job history by id or name
+    pub fn query_history(&self, id_or_name: &str, query: &HistoryQuery) -> Option<Vec<JobRecord>> {
+        self.with_ctx(id_or_name, |ctx| ctx.history.lock().unwrap().query(query))
+    }
+
+    /// The newest `n` failures of a job, oldest first
+    pub fn last_failures(&self, id_or_name: &str, n: usize) -> Option<Vec<JobRecord>> {
+        self.with_ctx(id_or_name, |ctx| {
+            ctx.history.lock().unwrap().last_failures(n)
+        })
+    }
+
+    /// Aggregated statistics of a job's history
+    pub fn stats(&self, id_or_name: &str) -> Option<JobStats> {
+        self.with_ctx(id_or_name, |ctx| ctx.history.lock().unwrap().stats())
     }
 
     /// List all jobs: (name, id)
@@ -238,6 +1567,15 @@ impl JobKit {
             .collect()
     }
 
+    fn with_ctx<R>(&self, id_or_name: &str, f: impl FnOnce(&Arc<JobContext>) -> R) -> Option<R> {
+        let id = self.resolve_id(id_or_name)?;
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|entry| f(&entry.ctx))
+    }
+
     fn resolve_id(&self, id_or_name: &str) -> Option<String> {
         if self.jobs.lock().unwrap().contains_key(id_or_name) {
             Some(id_or_name.to_string())
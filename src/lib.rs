@@ -1,5 +1,6 @@
 pub mod cache;
 pub mod cachekit;
+pub mod clockkit;
 pub mod collectionkit;
 pub mod compresskit;
 pub mod configkit;
@@ -13,6 +14,7 @@ pub mod hashkit;
 pub mod httpkit;
 pub mod idkit;
 pub mod internal;
+pub mod job;
 pub mod iokit;
 pub mod jobkit;
 pub mod jsonkit;
@@ -22,6 +24,7 @@ pub mod numberkit;
 pub mod oskit;
 pub mod processkit;
 pub mod randkit;
+pub mod registrykit;
 pub mod regkit;
 pub mod singlekit;
 pub mod stringkit;
@@ -25,7 +25,7 @@ pub fn is_negative(n: f64) -> bool {
 
 /// 判断是否为零（容忍误差）
 pub fn is_zero(n: f64) -> bool {
-    n.abs() < std::f64::EPSILON
+    n.abs() < f64::EPSILON
 }
 
 /// 判断是否为偶数
@@ -104,7 +104,7 @@ pub fn to_string<T: Display>(value: T) -> String {
 
 /// 安全除法，避免被除数为 0
 pub fn safe_div(a: f64, b: f64) -> Option<f64> {
-    if b.abs() < std::f64::EPSILON {
+    if b.abs() < f64::EPSILON {
         None
     } else {
         Some(a / b)
@@ -0,0 +1,369 @@
+use crate::singlekit::SingleRw;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, RwLock};
+use std::thread::{self, ThreadId};
+
+/// 服务的生命周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifetime {
+    /// 第一次解析时创建，之后一直复用
+    Singleton,
+    /// 每次解析都重新创建
+    Transient,
+}
+
+type Instance = Box<dyn Any + Send + Sync>;
+type ErasedFactory = dyn Fn(&Resolver) -> Result<Instance, String> + Send + Sync;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ServiceKey {
+    type_id: TypeId,
+    name: Option<String>,
+}
+
+struct Provider {
+    type_name: &'static str,
+    lifetime: Lifetime,
+    factory: Box<ErasedFactory>,
+    /// 单例已创建的实例，存的是 `Arc<T>`
+    instance: Mutex<Option<Instance>>,
+}
+
+/// 正在创建的单例：谁在创建，以及哪些线程在等待哪个单例。
+/// 等待前沿着等待关系检查，跨线程的循环依赖返回错误而不是死锁
+#[derive(Default)]
+struct Building {
+    owners: HashMap<ServiceKey, ThreadId>,
+    waiting: HashMap<ThreadId, ServiceKey>,
+}
+
+/// 服务注册表：按类型或名字注册工厂，解析时按需创建并注入依赖。
+/// 服务以 `Arc<T>` 返回，`T` 可以是 `dyn Trait`。重复注册会替换旧的工厂和已创建的单例，测试里可以借此换成 mock。
+/// ```
+/// use rovkit::registrykit::{Lifetime, Registry};
+/// use std::sync::Arc;
+///
+/// trait Greeter: Send + Sync {
+///     fn greet(&self) -> String;
+/// }
+///
+/// struct Hello {
+///     name: Arc<String>,
+/// }
+///
+/// impl Greeter for Hello {
+///     fn greet(&self) -> String {
+///         format!("hello {}", self.name)
+///     }
+/// }
+///
+/// let mut registry = Registry::new();
+/// registry
+///     .register_instance(Arc::new("rovkit".to_string()))
+///     .register::<dyn Greeter>(Lifetime::Singleton, |r| {
+///         Ok(Arc::new(Hello { name: r.resolve()? }))
+///     });
+/// assert_eq!(registry.resolve::<dyn Greeter>().unwrap().greet(), "hello rovkit");
+/// ```
+#[derive(Default)]
+pub struct Registry {
+    providers: HashMap<ServiceKey, Provider>,
+    building: Mutex<Building>,
+    built: Condvar,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 按类型注册
+    pub fn register<T>(
+        &mut self,
+        lifetime: Lifetime,
+        factory: impl Fn(&Resolver) -> Result<Arc<T>, String> + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.insert(Self::key::<T>(None), lifetime, factory)
+    }
+
+    /// 按名字注册，同一类型可以有多个名字不同的实现
+    pub fn register_named<T>(
+        &mut self,
+        name: &str,
+        lifetime: Lifetime,
+        factory: impl Fn(&Resolver) -> Result<Arc<T>, String> + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.insert(Self::key::<T>(Some(name)), lifetime, factory)
+    }
+
+    /// 注册已经创建好的单例
+    pub fn register_instance<T>(&mut self, instance: Arc<T>) -> &mut Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.register(Lifetime::Singleton, move |_| Ok(instance.clone()))
+    }
+
+    pub fn contains<T: ?Sized + 'static>(&self) -> bool {
+        self.providers.contains_key(&Self::key::<T>(None))
+    }
+
+    pub fn contains_named<T: ?Sized + 'static>(&self, name: &str) -> bool {
+        self.providers.contains_key(&Self::key::<T>(Some(name)))
+    }
+
+    /// 删除注册，返回是否存在
+    pub fn remove<T: ?Sized + 'static>(&mut self) -> bool {
+        self.providers.remove(&Self::key::<T>(None)).is_some()
+    }
+
+    pub fn remove_named<T: ?Sized + 'static>(&mut self, name: &str) -> bool {
+        self.providers.remove(&Self::key::<T>(Some(name))).is_some()
+    }
+
+    /// 解析服务，未注册、创建失败或存在循环依赖时返回错误
+    pub fn resolve<T>(&self) -> Result<Arc<T>, String>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        Resolver::new(self).resolve()
+    }
+
+    pub fn resolve_named<T>(&self, name: &str) -> Result<Arc<T>, String>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        Resolver::new(self).resolve_named(name)
+    }
+
+    fn key<T: ?Sized + 'static>(name: Option<&str>) -> ServiceKey {
+        ServiceKey {
+            type_id: TypeId::of::<T>(),
+            name: name.map(str::to_string),
+        }
+    }
+
+    fn insert<T, F>(&mut self, key: ServiceKey, lifetime: Lifetime, factory: F) -> &mut Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        F: Fn(&Resolver) -> Result<Arc<T>, String> + Send + Sync + 'static,
+    {
+        let provider = Provider {
+            type_name: std::any::type_name::<T>(),
+            lifetime,
+            factory: Box::new(move |r| factory(r).map(|service| Box::new(service) as Instance)),
+            instance: Mutex::new(None),
+        };
+        self.providers.insert(key, provider);
+        self
+    }
+}
+
+/// 解析上下文，传给工厂用来解析依赖，同时记录解析链以检测循环依赖
+pub struct Resolver<'a> {
+    registry: &'a Registry,
+    resolving: RefCell<Vec<(ServiceKey, &'static str)>>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(registry: &'a Registry) -> Self {
+        Self {
+            registry,
+            resolving: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn resolve<T>(&self) -> Result<Arc<T>, String>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.resolve_key(Registry::key::<T>(None))
+    }
+
+    pub fn resolve_named<T>(&self, name: &str) -> Result<Arc<T>, String>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.resolve_key(Registry::key::<T>(Some(name)))
+    }
+
+    fn resolve_key<T>(&self, key: ServiceKey) -> Result<Arc<T>, String>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let Some(provider) = self.registry.providers.get(&key) else {
+            return Err(format!(
+                "service not registered: {}",
+                Self::describe(&key, std::any::type_name::<T>())
+            ));
+        };
+        if let Some(start) = self.resolving.borrow().iter().position(|(k, _)| *k == key) {
+            let chain: Vec<String> = self.resolving.borrow()[start..]
+                .iter()
+                .map(|(k, type_name)| Self::describe(k, type_name))
+                .chain(std::iter::once(Self::describe(&key, provider.type_name)))
+                .collect();
+            return Err(format!("circular dependency: {}", chain.join(" -> ")));
+        }
+
+        self.resolving
+            .borrow_mut()
+            .push((key.clone(), provider.type_name));
+        let result = self.create(&key, provider);
+        self.resolving.borrow_mut().pop();
+        result
+    }
+
+    /// 单例创建失败时不保存，下次解析重试。
+    /// 工厂运行时不持有锁，同一单例同时只有一个线程在创建，其他线程等待结果
+    fn create<T>(&self, key: &ServiceKey, provider: &Provider) -> Result<Arc<T>, String>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let downcast = |instance: &Instance| {
+            instance
+                .downcast_ref::<Arc<T>>()
+                .cloned()
+                .ok_or_else(|| format!("service type mismatch: {}", provider.type_name))
+        };
+        if provider.lifetime == Lifetime::Transient {
+            return downcast(&(provider.factory)(self)?);
+        }
+
+        let me = thread::current().id();
+        let mut building = self.building();
+        loop {
+            // 只在读取时持有实例锁，创建者放入实例时需要拿到它
+            if let Some(instance) = provider
+                .instance
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .as_ref()
+            {
+                return downcast(instance);
+            }
+            let Some(&owner) = building.owners.get(key) else {
+                break;
+            };
+            self.check_wait(&building, key, owner, me)?;
+            building.waiting.insert(me, key.clone());
+            building = self
+                .registry
+                .built
+                .wait(building)
+                .unwrap_or_else(PoisonError::into_inner);
+            building.waiting.remove(&me);
+        }
+        building.owners.insert(key.clone(), me);
+        drop(building);
+
+        let _owned = Owned {
+            registry: self.registry,
+            key,
+        };
+        let instance = (provider.factory)(self)?;
+        let service = downcast(&instance);
+        // 放入实例后 `_owned` 才释放，等待的线程醒来时能直接拿到
+        *provider
+            .instance
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(instance);
+        service
+    }
+
+    fn building(&self) -> MutexGuard<'a, Building> {
+        self.registry
+            .building
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 沿着“创建者在等待谁”走下去，回到当前线程说明存在循环依赖
+    fn check_wait(
+        &self,
+        building: &Building,
+        key: &ServiceKey,
+        owner: ThreadId,
+        me: ThreadId,
+    ) -> Result<(), String> {
+        let mut path = vec![key];
+        let mut owner = owner;
+        while owner != me {
+            let Some(next) = building.waiting.get(&owner) else {
+                return Ok(());
+            };
+            let Some(&next_owner) = building.owners.get(next) else {
+                return Ok(());
+            };
+            path.push(next);
+            owner = next_owner;
+        }
+        // path 最后一个由当前线程创建，它依赖 path[0]
+        let mine = path.pop().unwrap_or(key);
+        let chain: Vec<String> = std::iter::once(mine)
+            .chain(path)
+            .chain(std::iter::once(mine))
+            .map(|k| self.describe_key(k))
+            .collect();
+        Err(format!("circular dependency: {}", chain.join(" -> ")))
+    }
+
+    fn describe_key(&self, key: &ServiceKey) -> String {
+        let type_name = self
+            .registry
+            .providers
+            .get(key)
+            .map_or("?", |provider| provider.type_name);
+        Self::describe(key, type_name)
+    }
+
+    fn describe(key: &ServiceKey, type_name: &str) -> String {
+        match &key.name {
+            Some(name) => format!("{}({})", type_name, name),
+            None => type_name.to_string(),
+        }
+    }
+}
+
+/// 当前线程正在创建某个单例，结束（包括失败和 panic）时放开并唤醒等待的线程
+struct Owned<'a> {
+    registry: &'a Registry,
+    key: &'a ServiceKey,
+}
+
+impl Drop for Owned<'_> {
+    fn drop(&mut self) {
+        let mut building = self
+            .registry
+            .building
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        building.owners.remove(self.key);
+        self.registry.built.notify_all();
+    }
+}
+
+/// 全局注册表
+pub fn global() -> &'static RwLock<Registry> {
+    static GLOBAL: SingleRw<Registry> = SingleRw::new();
+    GLOBAL.get_or_init(Registry::new)
+}
+
+/// 从全局注册表解析服务
+pub fn resolve<T>() -> Result<Arc<T>, String>
+where
+    T: ?Sized + Send + Sync + 'static,
+{
+    global()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .resolve()
+}
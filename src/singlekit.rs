@@ -1,42 +1,157 @@
-use std::sync::{Mutex, OnceLock};
+use crate::internal::gate::Gate;
+use std::convert::Infallible;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError, RwLock};
 
-/// 通用单例包装器
-pub struct Single<T> {
-    instance: OnceLock<Mutex<T>>,
+/// 单例内部的锁，`Mutex` 或 `RwLock`
+pub trait SingleLock<T> {
+    fn wrap(value: T) -> Self;
+    /// 替换锁内的值，`reset` 后重新初始化时使用
+    fn replace(&self, value: T);
 }
 
-impl<T> Single<T> {
+impl<T> SingleLock<T> for Mutex<T> {
+    fn wrap(value: T) -> Self {
+        Mutex::new(value)
+    }
+
+    fn replace(&self, value: T) {
+        *self.lock().unwrap_or_else(PoisonError::into_inner) = value;
+    }
+}
+
+impl<T> SingleLock<T> for RwLock<T> {
+    fn wrap(value: T) -> Self {
+        RwLock::new(value)
+    }
+
+    fn replace(&self, value: T) {
+        *self.write().unwrap_or_else(PoisonError::into_inner) = value;
+    }
+}
+
+/// 通用单例包装器，默认用 `Mutex` 保护，读多写少时用 `SingleRw`
+pub struct Single<T, L = Mutex<T>> {
+    instance: OnceLock<L>,
+    /// 初始化串行执行，失败时不保存，下次调用重试
+    init: Gate,
+    /// `reset` 后为 true，下次初始化替换旧值
+    stale: AtomicBool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// 用 `RwLock` 保护的单例
+pub type SingleRw<T> = Single<T, RwLock<T>>;
+
+impl<T, L: SingleLock<T>> Single<T, L> {
     /// 创建新的单例容器
     pub const fn new() -> Self {
         Self {
             instance: OnceLock::new(),
+            init: Gate::new(),
+            stale: AtomicBool::new(false),
+            _marker: PhantomData,
         }
     }
 
     /// 获取单例引用，如果未初始化则使用提供的闭包初始化
-    pub fn get_or_init<F>(&'static self, init: F) -> &'static Mutex<T>
+    pub fn get_or_init<F>(&'static self, init: F) -> &'static L
     where
         F: FnOnce() -> T,
     {
-        self.instance.get_or_init(|| Mutex::new(init()))
+        match self.get_or_try_init(|| Ok::<T, Infallible>(init())) {
+            Ok(lock) => lock,
+            Err(never) => match never {},
+        }
+    }
+
+    /// 获取单例引用，未初始化时用 `init` 初始化，失败时返回错误，下次调用重试
+    pub fn get_or_try_init<E, F>(&'static self, init: F) -> Result<&'static L, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        if let Some(lock) = self.get() {
+            return Ok(lock);
+        }
+        let _guard = self.init.enter();
+        if let Some(lock) = self.get() {
+            return Ok(lock);
+        }
+        Ok(self.store(init()?))
+    }
+
+    /// `get_or_init` 的异步版本，等待其他调用方初始化时不阻塞线程
+    pub async fn get_or_init_async<F, Fut>(&'static self, init: F) -> &'static L
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let result = self
+            .get_or_try_init_async(|| async { Ok::<T, Infallible>(init().await) })
+            .await;
+        match result {
+            Ok(lock) => lock,
+            Err(never) => match never {},
+        }
+    }
+
+    /// `get_or_try_init` 的异步版本
+    pub async fn get_or_try_init_async<E, F, Fut>(&'static self, init: F) -> Result<&'static L, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        if let Some(lock) = self.get() {
+            return Ok(lock);
+        }
+        let _guard = self.init.enter_async().await;
+        if let Some(lock) = self.get() {
+            return Ok(lock);
+        }
+        Ok(self.store(init().await?))
     }
 
     /// 获取单例引用，必须已经初始化
-    pub fn get(&'static self) -> Option<&'static Mutex<T>> {
+    pub fn get(&'static self) -> Option<&'static L> {
+        if self.stale.load(Ordering::Acquire) {
+            return None;
+        }
         self.instance.get()
     }
+
+    /// 仅用于测试，需要开启 `test-util` feature：把单例标记为未初始化，下次 `get_or_init` 重新初始化。
+    /// 已经拿到的引用仍然有效，重新初始化后能看到新值
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn reset(&self) {
+        if self.instance.get().is_some() {
+            self.stale.store(true, Ordering::Release);
+        }
+    }
+
+    fn store(&'static self, value: T) -> &'static L {
+        match self.instance.get() {
+            // 只有 `reset` 之后才替换旧值
+            Some(lock) if self.stale.load(Ordering::Acquire) => {
+                lock.replace(value);
+                self.stale.store(false, Ordering::Release);
+                lock
+            }
+            Some(lock) => lock,
+            None => self.instance.get_or_init(|| L::wrap(value)),
+        }
+    }
 }
 
 // 为所有T实现Default
-impl<T> Default for Single<T> {
+impl<T, L: SingleLock<T>> Default for Single<T, L> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-// 线程安全标记
-unsafe impl<T> Sync for Single<T> where T: Sync {}
-/// 通用单例包装器
+/// 通用单例包装器，写成 `RwLock<类型>` 时用 `RwLock` 保护，否则用 `Mutex`
 /// ```
 /// use rovkit::*;
 ///
@@ -49,20 +164,33 @@ unsafe impl<T> Sync for Single<T> where T: Sync {}
 ///    name: "test".to_string(),
 /// });
 ///
+/// struct Settings {
+///     debug: bool,
+/// }
+///
+/// singleton!(SETTINGS: RwLock<Settings> = Settings { debug: false });
+///
+/// assert!(!Settings::single().read().unwrap().debug);
 ///```
 ///
 #[macro_export]
 macro_rules! singleton {
-    ($vis:vis $name:ident: $ty:ty = $expr:expr) => {
-        use once_cell::sync::Lazy;
-        use std::sync::Mutex;
+    ($vis:vis $name:ident: RwLock<$ty:ty> = $expr:expr) => {
+        $vis static $name: ::std::sync::LazyLock<::std::sync::RwLock<$ty>> =
+            ::std::sync::LazyLock::new(|| ::std::sync::RwLock::new($expr));
 
-        $vis static $name: Lazy<Mutex<$ty>> = Lazy::new(|| {
-            Mutex::new($expr)
-        });
+        impl $ty {
+            $vis fn single() -> &'static ::std::sync::RwLock<Self> {
+                &$name
+            }
+        }
+    };
+    ($vis:vis $name:ident: $ty:ty = $expr:expr) => {
+        $vis static $name: ::std::sync::LazyLock<::std::sync::Mutex<$ty>> =
+            ::std::sync::LazyLock::new(|| ::std::sync::Mutex::new($expr));
 
         impl $ty {
-            $vis fn single() -> &'static Mutex<Self> {
+            $vis fn single() -> &'static ::std::sync::Mutex<Self> {
                 &$name
             }
         }
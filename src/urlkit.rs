@@ -59,7 +59,7 @@ pub fn to_string(url: &Url) -> String {
 pub fn get_path_segments(url: &Url) -> Vec<String> {
     url.path_segments()
         .map(|segments| segments.map(|s| s.to_string()).collect())
-        .unwrap_or_else(Vec::new)
+        .unwrap_or_default()
 }
 
 /// 获取 fragment（#后部分）
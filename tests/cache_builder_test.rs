@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::cachekit::{time_cache_with_clock, Cache, CacheBuilder};
+    use rovkit::clockkit::MockClock;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_max_entries() {
+        let mut cache = CacheBuilder::new().max_entries(2).build();
+        cache.put(1, "one");
+        cache.put(2, "two");
+        cache.get(&1);
+        cache.put(3, "three"); // 淘汰最久未用的 2
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some(&"one"));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.capacity(), 2);
+    }
+
+    #[test]
+    fn test_max_weight() {
+        let mut cache = CacheBuilder::new()
+            .max_weight(10)
+            .weigher(|_k: &&str, v: &String| v.len() as u64)
+            .build();
+        cache.put("a", "1234".to_string());
+        cache.put("b", "1234".to_string());
+        assert_eq!(cache.weight(), 8);
+
+        cache.put("c", "123".to_string()); // 超重，淘汰 a
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(cache.weight(), 7);
+
+        cache.put("b", "1".to_string());
+        assert_eq!(cache.weight(), 4, "覆盖写入应更新权重");
+
+        cache.put("huge", "x".repeat(11));
+        assert_eq!(cache.get(&"huge"), None, "单个条目超重时不缓存");
+        assert_eq!(cache.len(), 2);
+
+        assert_eq!(cache.remove(&"c"), Some("123".to_string()));
+        assert_eq!(cache.weight(), 1);
+    }
+
+    #[test]
+    fn test_ttl_and_tti() {
+        let clock = Arc::new(MockClock::default());
+        let mut cache = CacheBuilder::new()
+            .time_to_live(Duration::from_secs(10))
+            .time_to_idle(Duration::from_secs(3))
+            .clock(clock.clone())
+            .build();
+        cache.put("hot", 1);
+        cache.put("idle", 2);
+
+        for _ in 0..4 {
+            clock.advance(Duration::from_secs(2));
+            assert_eq!(cache.get(&"hot"), Some(&1));
+        }
+        assert_eq!(cache.get(&"idle"), None, "超过 TTI 未访问应过期");
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(cache.get(&"hot"), None, "持续访问也不能超过 TTL");
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_put_with_ttl() {
+        let clock = Arc::new(MockClock::default());
+        let mut cache = CacheBuilder::new()
+            .max_entries(10)
+            .time_to_live(Duration::from_secs(60))
+            .clock(clock.clone())
+            .build();
+        cache.put("default", 1);
+        cache.put_with_ttl("short", 2, Duration::from_secs(5));
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(cache.get(&"short"), None);
+        assert_eq!(cache.get(&"default"), Some(&1));
+
+        clock.advance(Duration::from_secs(55));
+        assert_eq!(cache.get(&"default"), None);
+    }
+
+    #[test]
+    fn test_time_cache_is_cache() {
+        let clock = Arc::new(MockClock::default());
+        let mut cache: Box<dyn Cache<&str, i32>> =
+            Box::new(time_cache_with_clock(1, clock.clone()));
+        cache.put("a", 1);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(cache.get(&"a"), None);
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::cachekit::{
+        concurrent_fifo_cache, concurrent_lru_cache, concurrent_time_cache_with_clock,
+        ConcurrentCache,
+    };
+    use rovkit::clockkit::MockClock;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    fn fill_from_threads(cache: &Arc<dyn ConcurrentCache<u32, Arc<String>>>) {
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                let cache = cache.clone();
+                thread::spawn(move || {
+                    for i in 0..500 {
+                        let key = t * 1000 + i;
+                        cache.put(key, Arc::new(key.to_string()));
+                        // 其他线程写入可能已把它淘汰
+                        if let Some(value) = cache.get(&key) {
+                            assert_eq!(*value, key.to_string());
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_concurrent_fifo() {
+        let cache = concurrent_fifo_cache::<u32, Arc<String>>(1000);
+        assert_eq!(cache.capacity(), 1000);
+        fill_from_threads(&cache);
+        assert_eq!(cache.len(), 1000, "容量满后应按分片淘汰");
+
+        cache.put(1, Arc::new("one".to_string()));
+        assert_eq!(cache.get(&1).as_deref().map(String::as_str), Some("one"));
+        assert!(cache.remove(&1).is_some());
+        assert!(cache.get(&1).is_none());
+    }
+
+    #[test]
+    fn test_concurrent_lru() {
+        let cache = concurrent_lru_cache::<u32, Arc<String>>(1000);
+        fill_from_threads(&cache);
+        assert_eq!(cache.len(), 1000);
+
+        let single = concurrent_lru_cache::<i32, &'static str>(1);
+        single.put(1, "one");
+        single.put(2, "two");
+        assert_eq!(single.get(&1), None);
+        assert_eq!(single.get(&2), Some("two"));
+    }
+
+    #[test]
+    fn test_small_cache_keeps_capacity() {
+        for capacity in [1, 2, 8, 16, 31] {
+            let lru = concurrent_lru_cache::<usize, usize>(capacity);
+            let fifo = concurrent_fifo_cache::<usize, usize>(capacity);
+            for key in 0..capacity {
+                lru.put(key, key);
+                fifo.put(key, key);
+            }
+            assert_eq!(lru.len(), capacity, "lru {}", capacity);
+            assert_eq!(fifo.len(), capacity, "fifo {}", capacity);
+        }
+    }
+
+    #[test]
+    fn test_concurrent_time() {
+        let clock = Arc::new(MockClock::default());
+        let cache = concurrent_time_cache_with_clock::<&'static str, i32>(2, clock.clone());
+        assert!(cache.is_empty());
+        cache.put("a", 1);
+        cache.put("b", 2);
+        assert_eq!(cache.get(&"a"), Some(1));
+
+        clock.advance(Duration::from_secs(1));
+        cache.put("b", 3);
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(cache.get(&"a"), None, "a 已过期");
+        assert_eq!(cache.get(&"b"), Some(3));
+        assert_eq!(cache.len(), 1);
+    }
+}
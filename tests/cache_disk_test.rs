@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::cachekit::{lru_cache, Cache, DiskCache, TieredCache};
+    use serde::{Deserialize, Serialize};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Response {
+        status: u16,
+        body: String,
+    }
+
+    fn response(body: &str) -> Response {
+        Response {
+            status: 200,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_disk_cache_persist() {
+        let dir = tempdir().unwrap();
+        {
+            let mut cache = DiskCache::open(dir.path(), 1 << 20).unwrap();
+            cache.put(&"a".to_string(), &response("hello")).unwrap();
+            cache.put(&"b".to_string(), &response("world")).unwrap();
+            assert!(cache.remove(&"b".to_string()).unwrap());
+        }
+
+        // 重新打开后数据仍在
+        let mut cache: DiskCache<String, Response> = DiskCache::open(dir.path(), 1 << 20).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(
+            cache.get(&"a".to_string()).unwrap(),
+            Some(response("hello"))
+        );
+        assert_eq!(cache.get(&"b".to_string()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_disk_cache_lru_eviction() {
+        let dir = tempdir().unwrap();
+        let size = serde_json::to_string(&response("x")).unwrap().len() as u64;
+        let mut cache = DiskCache::open(dir.path(), size * 2).unwrap();
+        cache.put(&1, &response("x")).unwrap();
+        cache.put(&2, &response("y")).unwrap();
+        cache.get(&1).unwrap();
+        cache.put(&3, &response("z")).unwrap();
+
+        assert_eq!(cache.size_bytes(), size * 2);
+        assert!(cache.contains_key(&1).unwrap());
+        assert!(!cache.contains_key(&2).unwrap(), "最久未使用的应被淘汰");
+        assert!(cache.contains_key(&3).unwrap());
+        let files = fs::read_dir(dir.path())
+            .unwrap()
+            .filter(|f| f.as_ref().unwrap().path().extension().unwrap() == "data")
+            .count();
+        assert_eq!(files, 2);
+
+        // 超过上限的单个值不写入
+        cache.put(&4, &response(&"x".repeat(100))).unwrap();
+        assert!(!cache.contains_key(&4).unwrap());
+    }
+
+    #[test]
+    fn test_disk_cache_corrupted_file() {
+        let dir = tempdir().unwrap();
+        let mut cache = DiskCache::open(dir.path(), 1 << 20).unwrap();
+        cache.put(&"k", &response("v")).unwrap();
+        for file in fs::read_dir(dir.path()).unwrap() {
+            let path = file.unwrap().path();
+            if path.extension().unwrap() == "data" {
+                fs::write(path, "{broken").unwrap();
+            }
+        }
+        assert_eq!(cache.get(&"k").unwrap(), None);
+        assert!(cache.is_empty());
+
+        // 不是 UTF-8 的内容同样当作损坏
+        cache.put(&"k", &response("v")).unwrap();
+        for file in fs::read_dir(dir.path()).unwrap() {
+            let path = file.unwrap().path();
+            if path.extension().unwrap() == "data" {
+                fs::write(path, [0xff, 0xfe]).unwrap();
+            }
+        }
+        assert_eq!(cache.get(&"k").unwrap(), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_disk_cache_orphan_files() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("dangling.data"), "1").unwrap();
+        fs::write(dir.path().join("abc.data.tmp"), "1").unwrap();
+        let cache: DiskCache<String, i32> = DiskCache::open(dir.path(), 1024).unwrap();
+        assert!(cache.is_empty());
+        assert!(!dir.path().join("dangling.data").exists());
+        assert!(
+            !dir.path().join("abc.data.tmp").exists(),
+            "崩溃留下的临时文件应删除"
+        );
+    }
+
+    #[test]
+    fn test_disk_cache_index_log() {
+        let dir = tempdir().unwrap();
+        let index = dir.path().join("index.jsonl");
+        let lines = || fs::read_to_string(&index).unwrap().lines().count();
+
+        let mut cache = DiskCache::open(dir.path(), 1 << 20).unwrap();
+        for i in 0..3 {
+            cache.put(&i, &response("v")).unwrap();
+        }
+        cache.remove(&1).unwrap();
+        assert_eq!(lines(), 4, "写入和删除只追加一行");
+        // 不经过 Drop，模拟进程崩溃
+        std::mem::forget(cache);
+
+        let mut cache: DiskCache<i32, Response> = DiskCache::open(dir.path(), 1 << 20).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains_key(&1).unwrap());
+        assert_eq!(cache.get(&2).unwrap(), Some(response("v")));
+
+        for _ in 0..200 {
+            cache.put(&0, &response("hot")).unwrap();
+        }
+        assert!(lines() <= 65, "日志应被压缩，实际 {} 行", lines());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_tiered_cache() {
+        let dir = tempdir().unwrap();
+        {
+            let disk = DiskCache::open(dir.path(), 1 << 20).unwrap();
+            let mut cache = TieredCache::new(lru_cache(1), disk);
+            cache.put("a".to_string(), response("1"));
+            cache.put("b".to_string(), response("2"));
+            assert_eq!(cache.memory().len(), 1);
+            assert_eq!(cache.len(), 2);
+            // 内存层已淘汰，从磁盘读回
+            assert_eq!(cache.get(&"a".to_string()), Some(&response("1")));
+        }
+
+        let disk = DiskCache::open(dir.path(), 1 << 20).unwrap();
+        let mut cache = TieredCache::new(lru_cache(10), disk);
+        assert_eq!(cache.get(&"b".to_string()), Some(&response("2")));
+        assert_eq!(cache.remove(&"a".to_string()), Some(response("1")));
+        assert_eq!(cache.len(), 1);
+    }
+}
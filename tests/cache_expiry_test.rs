@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::cachekit::{
+        spawn_sweeper, Cache, CacheBuilder, ExpiringCache, RemovalCause, TimedCache,
+    };
+    use rovkit::clockkit::MockClock;
+    use std::hash::{Hash, Hasher};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    type Events = Arc<Mutex<Vec<(String, i32, RemovalCause)>>>;
+
+    fn recorder() -> (Events, impl Fn(&&str, &i32, RemovalCause) + Send + Sync) {
+        let events: Events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let listener = move |k: &&str, v: &i32, cause: RemovalCause| {
+            sink.lock().unwrap().push((k.to_string(), *v, cause));
+        };
+        (events, listener)
+    }
+
+    fn take(events: &Events) -> Vec<(String, i32, RemovalCause)> {
+        std::mem::take(&mut *events.lock().unwrap())
+    }
+
+    #[test]
+    fn test_timed_cache_listener() {
+        let clock = Arc::new(MockClock::default());
+        let mut cache = TimedCache::with_clock(Duration::from_secs(10), clock.clone());
+        let (events, listener) = recorder();
+        cache.set_eviction_listener(listener);
+
+        cache.put("a", 1);
+        cache.put("a", 2);
+        cache.put("b", 3);
+        assert_eq!(cache.remove(&"b"), Some(3));
+        assert_eq!(
+            take(&events),
+            vec![
+                ("a".to_string(), 1, RemovalCause::Replaced),
+                ("b".to_string(), 3, RemovalCause::Explicit),
+            ]
+        );
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(
+            take(&events),
+            vec![("a".to_string(), 2, RemovalCause::Expired)]
+        );
+    }
+
+    #[test]
+    fn test_timed_cache_purge() {
+        let clock = Arc::new(MockClock::default());
+        let mut cache = TimedCache::with_clock(Duration::from_secs(10), clock.clone());
+        let (events, listener) = recorder();
+        cache.set_eviction_listener(listener);
+
+        cache.put("old", 1);
+        clock.advance(Duration::from_secs(5));
+        cache.put("new", 2);
+        cache.put("old", 3); // 覆盖后重新计时
+        clock.advance(Duration::from_secs(5));
+        cache.put("other", 4);
+
+        assert_eq!(cache.purge_expired(), 0);
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(cache.purge_expired(), 2, "从未被读取的 key 也应清理");
+        assert_eq!(cache.len(), 1);
+        let expired: Vec<_> = take(&events)
+            .into_iter()
+            .filter(|(_, _, cause)| *cause == RemovalCause::Expired)
+            .map(|(k, _, _)| k)
+            .collect();
+        assert_eq!(expired, vec!["new", "old"]);
+
+        clock.advance(Duration::from_secs(5));
+        cache.put("fresh", 5);
+        assert_eq!(cache.len(), 1, "写入时顺带清理过期条目");
+    }
+
+    /// 统计存活的 key 副本数，`order` 中的每条记录都持有一个副本
+    struct Key {
+        id: u32,
+        live: Arc<AtomicUsize>,
+    }
+
+    impl Key {
+        fn new(id: u32, live: &Arc<AtomicUsize>) -> Self {
+            live.fetch_add(1, Ordering::SeqCst);
+            Self {
+                id,
+                live: live.clone(),
+            }
+        }
+    }
+
+    impl Clone for Key {
+        fn clone(&self) -> Self {
+            Self::new(self.id, &self.live)
+        }
+    }
+
+    impl Drop for Key {
+        fn drop(&mut self) {
+            self.live.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    impl PartialEq for Key {
+        fn eq(&self, other: &Self) -> bool {
+            self.id == other.id
+        }
+    }
+
+    impl Eq for Key {}
+
+    impl Hash for Key {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.id.hash(state);
+        }
+    }
+
+    #[test]
+    fn test_timed_cache_order_compacted() {
+        let clock = Arc::new(MockClock::default());
+        let live = Arc::new(AtomicUsize::new(0));
+        let mut cache = TimedCache::with_clock(Duration::from_secs(10), clock.clone());
+
+        for i in 0..1000 {
+            clock.advance(Duration::from_millis(1));
+            cache.put(Key::new(i % 4, &live), i);
+            cache.put(Key::new(100 + i, &live), i);
+            cache.remove(&Key::new(100 + i, &live));
+        }
+        // 4 个 key 在 map 和 order 中各一份，旧记录不应无限累积
+        assert_eq!(cache.len(), 4);
+        assert!(live.load(Ordering::SeqCst) <= 40, "{}", live.load(Ordering::SeqCst));
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(cache.purge_expired(), 4);
+        assert_eq!(live.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_composed_cache_listener() {
+        let clock = Arc::new(MockClock::default());
+        let (events, listener) = recorder();
+        let mut cache = CacheBuilder::new()
+            .max_entries(2)
+            .time_to_live(Duration::from_secs(10))
+            .clock(clock.clone())
+            .eviction_listener(listener)
+            .build();
+
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("b", 20);
+        cache.put("c", 3);
+        cache.remove(&"c");
+        assert_eq!(
+            take(&events),
+            vec![
+                ("b".to_string(), 2, RemovalCause::Replaced),
+                ("a".to_string(), 1, RemovalCause::Capacity),
+                ("c".to_string(), 3, RemovalCause::Explicit),
+            ]
+        );
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(cache.purge_expired(), 1);
+        assert!(cache.is_empty());
+        assert_eq!(
+            take(&events),
+            vec![("b".to_string(), 20, RemovalCause::Expired)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sweeper() {
+        let clock = Arc::new(MockClock::default());
+        let cache = Arc::new(Mutex::new(TimedCache::with_clock(
+            Duration::from_secs(1),
+            clock.clone(),
+        )));
+        let (events, listener) = recorder();
+        {
+            let mut cache = cache.lock().unwrap();
+            cache.set_eviction_listener(listener);
+            cache.put("a", 1);
+            cache.put("b", 2);
+        }
+        let sweeper = spawn_sweeper(&cache, Duration::from_millis(10));
+
+        clock.advance(Duration::from_secs(1));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(cache.lock().unwrap().len(), 0);
+        assert_eq!(take(&events).len(), 2);
+
+        drop(cache);
+        tokio::time::timeout(Duration::from_secs(1), sweeper)
+            .await
+            .expect("缓存释放后清理任务应退出")
+            .unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::cachekit::LoadingCache;
+    use rovkit::clockkit::MockClock;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let cache = LoadingCache::new(10);
+        assert_eq!(cache.get_or_insert_with("a", || 1), 1);
+        // 命中时不再调用
+        assert_eq!(cache.get_or_insert_with("a", || 2), 1);
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_get_or_load_error_not_cached() {
+        let cache: LoadingCache<&str, i32> = LoadingCache::new(10);
+        assert_eq!(cache.get_or_load("a", || Err("down")), Err("down"));
+        assert!(cache.is_empty());
+        assert_eq!(cache.get_or_load("a", || Ok::<_, &str>(3)), Ok(3));
+    }
+
+    #[test]
+    fn test_timed_purges_on_put() {
+        let cache: LoadingCache<u32, u32> = LoadingCache::timed(1);
+        for i in 0..100 {
+            cache.get_or_insert_with(i, || i);
+        }
+        assert_eq!(cache.len(), 100);
+        thread::sleep(Duration::from_millis(1100));
+        assert_eq!(cache.get_or_insert_with(0, || 1), 1, "过期后重新加载");
+        cache.get_or_insert_with(100, || 100);
+        assert!(cache.len() < 100, "写入时清理过期条目 {}", cache.len());
+    }
+
+    #[test]
+    fn test_single_flight() {
+        let cache = Arc::new(LoadingCache::new(10));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(8));
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let (cache, calls, barrier) = (cache.clone(), calls.clone(), barrier.clone());
+                thread::spawn(move || {
+                    barrier.wait();
+                    cache.get_or_insert_with("k", || {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(50));
+                        42
+                    })
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 42);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "并发加载应只执行一次");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_single_flight_mixed() {
+        let cache = Arc::new(LoadingCache::new(10));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let sync = {
+            let (cache, calls) = (cache.clone(), calls.clone());
+            tokio::task::spawn_blocking(move || {
+                cache.get_or_insert_with("k", || {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(100));
+                    1
+                })
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let loaded = cache
+            .get_or_load_async("k", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, String>(2)
+            })
+            .await;
+        assert_eq!(loaded, Ok(1));
+        assert_eq!(sync.await.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "同步和异步加载同一 key 只执行一次");
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_async() {
+        let cache = Arc::new(LoadingCache::new(10));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..8 {
+            let (cache, calls) = (cache.clone(), calls.clone());
+            tasks.spawn(async move {
+                cache
+                    .get_or_load_async("k", || async {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        Ok::<_, String>("v".to_string())
+                    })
+                    .await
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            assert_eq!(result.unwrap().unwrap(), "v");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_time_to_live() {
+        let clock = Arc::new(MockClock::default());
+        let cache = LoadingCache::new(10)
+            .time_to_live(Duration::from_secs(10))
+            .clock(clock.clone());
+        cache.put("a", 1);
+        clock.advance(Duration::from_secs(9));
+        assert_eq!(cache.get(&"a"), Some(1));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(cache.get_or_insert_with("a", || 2), 2);
+    }
+
+    #[test]
+    fn test_refresh_ahead() {
+        let clock = Arc::new(MockClock::default());
+        let version = Arc::new(AtomicUsize::new(1));
+        let source = version.clone();
+        let cache = LoadingCache::new(10)
+            .time_to_live(Duration::from_secs(10))
+            .refresh_ahead(Duration::from_secs(8), move |_: &&str| {
+                Ok(source.load(Ordering::SeqCst))
+            })
+            .clock(clock.clone());
+
+        assert_eq!(cache.get_or_insert_with("a", || 1), 1);
+        version.store(2, Ordering::SeqCst);
+        clock.advance(Duration::from_secs(8));
+        // 先返回旧值，后台刷新
+        assert_eq!(cache.get(&"a"), Some(1));
+        for _ in 0..100 {
+            if cache.get(&"a") == Some(2) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(cache.get(&"a"), Some(2));
+
+        // 刷新后重新计时，原来的过期时间已不再生效
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(cache.get(&"a"), Some(2));
+    }
+
+    #[test]
+    fn test_loader_panic_does_not_poison_key() {
+        let cache = Arc::new(LoadingCache::new(10));
+        let loader = cache.clone();
+        let panicked = thread::spawn(move || loader.get_or_insert_with(1, || panic!("boom")));
+        assert!(panicked.join().is_err());
+        assert_eq!(cache.get_or_insert_with(1, || 5), 5);
+        assert_eq!(cache.get(&1), Some(5));
+    }
+
+    #[test]
+    fn test_refresh_panic_allows_next_refresh() {
+        let clock = Arc::new(MockClock::default());
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let cache = LoadingCache::new(10)
+            .refresh_ahead(Duration::from_secs(1), move |_: &&str| {
+                match counter.fetch_add(1, Ordering::SeqCst) {
+                    0 => panic!("boom"),
+                    n => Ok(n + 1),
+                }
+            })
+            .clock(clock.clone());
+
+        assert_eq!(cache.get_or_insert_with("a", || 1), 1);
+        clock.advance(Duration::from_secs(1));
+        // 第一次刷新 panic，之后的读取仍能触发刷新
+        for _ in 0..100 {
+            if cache.get(&"a") == Some(2) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(cache.get(&"a"), Some(2));
+    }
+}
@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::memoize;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    static SQUARE_CALLS: AtomicUsize = AtomicUsize::new(0);
+    static PARSE_CALLS: AtomicUsize = AtomicUsize::new(0);
+    static FETCH_CALLS: AtomicUsize = AtomicUsize::new(0);
+    static TICK_CALLS: AtomicUsize = AtomicUsize::new(0);
+    static STAMP_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    memoize! {
+        lru(100);
+        fn fib(n: u64) -> u64 {
+            if n < 2 {
+                return n;
+            }
+            fib(n - 1) + fib(n - 2)
+        }
+    }
+
+    memoize! {
+        lru(10);
+        fn square(a: i64, label: String) -> String {
+            SQUARE_CALLS.fetch_add(1, Ordering::SeqCst);
+            format!("{}={}", label, a * a)
+        }
+    }
+
+    memoize! {
+        lru(10);
+        fn parse(s: String) -> Result<i64, String> {
+            PARSE_CALLS.fetch_add(1, Ordering::SeqCst);
+            let n = s.parse::<i64>().map_err(|e| e.to_string())?;
+            Ok(n)
+        }
+    }
+
+    memoize! {
+        lru(10);
+        async fn fetch(id: u32) -> Result<String, String> {
+            FETCH_CALLS.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if id == 0 {
+                return Err("not found".to_string());
+            }
+            Ok(format!("item-{}", id))
+        }
+    }
+
+    memoize! {
+        lru(10), time(1);
+        async fn tick() -> usize {
+            TICK_CALLS.fetch_add(1, Ordering::SeqCst) + 1
+        }
+    }
+
+    memoize! {
+        time(1);
+        fn stamp(id: u32) -> usize {
+            STAMP_CALLS.fetch_add(1, Ordering::SeqCst) + id as usize
+        }
+    }
+
+    #[test]
+    fn test_memoize_fib() {
+        assert_eq!(fib(90), 2880067194370816120);
+    }
+
+    #[test]
+    fn test_memoize_args() {
+        assert_eq!(square(3, "a".to_string()), "a=9");
+        assert_eq!(square(3, "a".to_string()), "a=9");
+        assert_eq!(square(3, "b".to_string()), "b=9");
+        assert_eq!(SQUARE_CALLS.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_memoize_result_only_ok() {
+        assert!(parse("x".to_string()).is_err());
+        assert!(parse("x".to_string()).is_err());
+        assert_eq!(PARSE_CALLS.load(Ordering::SeqCst), 2, "错误不缓存");
+        assert_eq!(parse("7".to_string()), Ok(7));
+        assert_eq!(parse("7".to_string()), Ok(7));
+        assert_eq!(PARSE_CALLS.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_memoize_async() {
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..5 {
+            tasks.spawn(fetch(1));
+        }
+        while let Some(result) = tasks.join_next().await {
+            assert_eq!(result.unwrap(), Ok("item-1".to_string()));
+        }
+        assert_eq!(FETCH_CALLS.load(Ordering::SeqCst), 1, "并发调用应合并");
+
+        assert!(fetch(0).await.is_err());
+        assert!(fetch(0).await.is_err());
+        assert_eq!(FETCH_CALLS.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_memoize_time() {
+        assert_eq!(tick().await, 1);
+        assert_eq!(tick().await, 1);
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        assert_eq!(tick().await, 2);
+    }
+
+    #[test]
+    fn test_memoize_time_only() {
+        assert_eq!(stamp(10), 10);
+        assert_eq!(stamp(10), 10);
+        std::thread::sleep(Duration::from_millis(1100));
+        assert_eq!(stamp(10), 11);
+    }
+}
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::cachekit::{
+        arc_cache, fifo_cache, lfu_cache, lru_cache, time_cache_with_clock, tinylfu_cache, Cache,
+        CacheBuilder, RemovalCause,
+    };
+    use rovkit::clockkit::MockClock;
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    type NamedCache = (&'static str, Box<dyn Cache<i32, String>>);
+
+    fn caches() -> Vec<NamedCache> {
+        let clock = Arc::new(MockClock::default());
+        vec![
+            ("fifo", fifo_cache(10)),
+            ("lru", lru_cache(10)),
+            ("time", Box::new(time_cache_with_clock(60, clock))),
+            ("lfu", lfu_cache(10)),
+            ("arc", arc_cache(10)),
+            ("tinylfu", tinylfu_cache(10)),
+            (
+                "builder",
+                Box::new(CacheBuilder::new().max_entries(10).build()),
+            ),
+        ]
+    }
+
+    fn keys(cache: &dyn Cache<i32, String>) -> HashSet<i32> {
+        cache.keys().copied().collect()
+    }
+
+    #[test]
+    fn test_bulk_and_iter() {
+        for (name, mut cache) in caches() {
+            cache.put_all((0..5).map(|i| (i, i.to_string())).collect());
+            assert_eq!(cache.len(), 5, "{}", name);
+            assert_eq!(keys(cache.as_ref()), (0..5).collect(), "{}", name);
+            assert_eq!(
+                cache.get_all(&[1, 7, 3]),
+                vec![Some("1".to_string()), None, Some("3".to_string())],
+                "{}",
+                name
+            );
+            let values: HashSet<String> = cache.iter().map(|(_, v)| v.clone()).collect();
+            assert_eq!(values.len(), 5, "{}", name);
+
+            assert!(cache.contains_key(&4), "{}", name);
+            assert_eq!(cache.peek(&4), Some(&"4".to_string()), "{}", name);
+            assert!(!cache.contains_key(&9), "{}", name);
+
+            cache.retain(&mut |k, _| k % 2 == 0);
+            assert_eq!(keys(cache.as_ref()), HashSet::from([0, 2, 4]), "{}", name);
+            assert_eq!(cache.get(&1), None, "{}", name);
+
+            cache.clear();
+            assert!(cache.is_empty(), "{}", name);
+            assert_eq!(cache.iter().count(), 0, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_resize() {
+        for (name, mut cache) in caches() {
+            cache.put_all((0..10).map(|i| (i, i.to_string())).collect());
+            cache.resize(4);
+            assert_eq!(cache.len(), 4, "{}", name);
+            assert_eq!(cache.capacity(), 4, "{}", name);
+            cache.put(100, "100".to_string());
+            assert!(cache.len() <= 4, "{}", name);
+
+            cache.resize(8);
+            cache.put_all((200..204).map(|i| (i, i.to_string())).collect());
+            assert!(cache.len() <= 8, "{} {}", name, cache.len());
+        }
+    }
+
+    #[test]
+    fn test_peek_keeps_order() {
+        let mut cache = lru_cache(2);
+        cache.put(1, "a");
+        cache.put(2, "b");
+        // peek 不算使用，1 仍是最久未使用的
+        assert_eq!(cache.peek(&1), Some(&"a"));
+        cache.put(3, "c");
+        assert!(!cache.contains_key(&1));
+
+        let mut cache = lru_cache(3);
+        cache.put_all(vec![(1, "a"), (2, "b"), (3, "c")]);
+        cache.get(&1);
+        assert_eq!(cache.keys().copied().collect::<Vec<_>>(), vec![1, 3, 2]);
+        cache.resize(1);
+        assert_eq!(cache.keys().copied().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn test_fifo_resize_order() {
+        let mut cache = fifo_cache(4);
+        cache.put_all(vec![(1, 1), (2, 2), (3, 3), (4, 4)]);
+        cache.get(&1);
+        cache.resize(2);
+        assert_eq!(cache.keys().copied().collect::<Vec<_>>(), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_time_cache_ops() {
+        let clock = Arc::new(MockClock::default());
+        let mut cache = time_cache_with_clock(10, clock.clone());
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        cache.set_eviction_listener(move |k: &&str, _: &i32, cause| {
+            sink.lock().unwrap().push((k.to_string(), cause));
+        });
+
+        cache.put("a", 1);
+        clock.advance(Duration::from_secs(5));
+        cache.put("b", 2);
+        cache.put("c", 3);
+        clock.advance(Duration::from_secs(5));
+        // a 已过期，peek 和 iter 都跳过
+        assert_eq!(Cache::peek(&cache, &"a"), None);
+        assert_eq!(Cache::keys(&cache).count(), 2);
+
+        Cache::resize(&mut cache, 1);
+        assert_eq!(Cache::peek(&cache, &"c"), Some(&3));
+        Cache::clear(&mut cache);
+        let events = events.lock().unwrap();
+        assert!(events.contains(&("a".to_string(), RemovalCause::Expired)));
+        assert!(events.contains(&("b".to_string(), RemovalCause::Capacity)));
+        assert!(events.contains(&("c".to_string(), RemovalCause::Explicit)));
+    }
+}
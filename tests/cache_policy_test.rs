@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use rovkit::cachekit::{arc_cache, lfu_cache, lru_cache, tinylfu_cache, Cache};
+
+    /// Zipf 分布的访问序列，key 越小越热
+    fn zipf_trace(items: usize, exponent: f64, len: usize, seed: u64) -> Vec<u64> {
+        let weights: Vec<f64> = (1..=items)
+            .map(|rank| 1.0 / (rank as f64).powf(exponent))
+            .collect();
+        let total: f64 = weights.iter().sum();
+        let mut cdf = Vec::with_capacity(items);
+        let mut acc = 0.0;
+        for w in weights {
+            acc += w / total;
+            cdf.push(acc);
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len)
+            .map(|_| {
+                let p: f64 = rng.gen();
+                cdf.partition_point(|&c| c < p).min(items - 1) as u64
+            })
+            .collect()
+    }
+
+    /// 每隔一段插入一次性的顺序扫描，模拟批处理污染缓存
+    fn with_scans(trace: Vec<u64>, every: usize, scan_len: u64) -> Vec<u64> {
+        let mut next_scan_key = 1_000_000;
+        let mut out = Vec::new();
+        for (i, key) in trace.into_iter().enumerate() {
+            if i % every == 0 {
+                out.extend(next_scan_key..next_scan_key + scan_len);
+                next_scan_key += scan_len;
+            }
+            out.push(key);
+        }
+        out
+    }
+
+    /// 未命中时写入，返回命中率
+    fn hit_ratio(cache: &mut dyn Cache<u64, u64>, trace: &[u64]) -> f64 {
+        let mut hits = 0;
+        for &key in trace {
+            if cache.get(&key).is_some() {
+                hits += 1;
+            } else {
+                cache.put(key, key);
+            }
+        }
+        hits as f64 / trace.len() as f64
+    }
+
+    fn ratios(trace: &[u64], capacity: usize) -> [f64; 4] {
+        [
+            hit_ratio(lru_cache(capacity).as_mut(), trace),
+            hit_ratio(lfu_cache(capacity).as_mut(), trace),
+            hit_ratio(arc_cache(capacity).as_mut(), trace),
+            hit_ratio(tinylfu_cache(capacity).as_mut(), trace),
+        ]
+    }
+
+    #[test]
+    fn test_zipf_hit_ratio() {
+        let trace = zipf_trace(10_000, 0.9, 200_000, 7);
+        let [lru, lfu, arc, tinylfu] = ratios(&trace, 500);
+        println!("zipf: lru {lru:.3} lfu {lfu:.3} arc {arc:.3} tinylfu {tinylfu:.3}");
+
+        assert!(lfu > lru, "lfu {} <= lru {}", lfu, lru);
+        assert!(arc > lru, "arc {} <= lru {}", arc, lru);
+        assert!(tinylfu > lru + 0.03, "tinylfu {} vs lru {}", tinylfu, lru);
+    }
+
+    #[test]
+    fn test_scan_resistance() {
+        let trace = with_scans(zipf_trace(5_000, 1.0, 100_000, 11), 1_000, 400);
+        let [lru, lfu, arc, tinylfu] = ratios(&trace, 500);
+        println!("scan: lru {lru:.3} lfu {lfu:.3} arc {arc:.3} tinylfu {tinylfu:.3}");
+
+        assert!(arc > lru, "arc {} <= lru {}", arc, lru);
+        assert!(lfu > lru + 0.05, "lfu {} vs lru {}", lfu, lru);
+        assert!(tinylfu > lru + 0.05, "tinylfu {} vs lru {}", tinylfu, lru);
+    }
+
+    #[test]
+    fn test_lfu_evicts_least_frequent() {
+        let mut cache = lfu_cache::<&str, i32>(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.get(&"a");
+        cache.put("c", 3); // b 访问次数最少，被淘汰
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        assert_eq!(cache.get(&"c"), Some(&3));
+
+        cache.put("c", 30);
+        assert_eq!(cache.remove(&"c"), Some(30));
+        cache.put("d", 4);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.capacity(), 2);
+
+        // 删掉最小桶里唯一的 key 后，淘汰和缩容仍按访问次数进行
+        let mut cache = lfu_cache::<&str, i32>(3);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+        for _ in 0..3 {
+            cache.get(&"b");
+        }
+        cache.get(&"c");
+        cache.remove(&"a");
+        cache.resize(1);
+        assert_eq!(cache.peek(&"b"), Some(&2));
+        assert_eq!(cache.len(), 1);
+        cache.resize(2);
+        cache.put("d", 4);
+        cache.put("e", 5); // d 只访问过一次，被淘汰
+        assert_eq!(cache.peek(&"d"), None);
+        assert_eq!(cache.peek(&"b"), Some(&2));
+    }
+
+    #[test]
+    fn test_policies_basic() {
+        let caches: Vec<Box<dyn Cache<i32, i32>>> =
+            vec![lfu_cache(3), arc_cache(3), tinylfu_cache(3)];
+        for mut cache in caches {
+            for i in 0..10 {
+                cache.put(i, i * 10);
+                assert!(cache.len() <= 3);
+            }
+            cache.put(100, 1);
+            assert_eq!(cache.get(&100), Some(&1), "刚写入的 key 在窗口或 t1 中");
+            cache.put(100, 2);
+            assert_eq!(cache.get(&100), Some(&2));
+            assert_eq!(cache.remove(&100), Some(2));
+            assert_eq!(cache.get(&100), None);
+        }
+
+        for mut cache in [lfu_cache::<i32, i32>(0), arc_cache(0), tinylfu_cache(0)] {
+            cache.put(1, 1);
+            assert!(cache.is_empty());
+        }
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::cachekit::{
+        arc_cache, concurrent_lru_cache, fifo_cache, lfu_cache, lru_cache, tinylfu_cache, Cache,
+        CacheBuilder, CacheStats, LoadingCache, RemovalCause,
+    };
+    use rovkit::clockkit::MockClock;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stats_disabled_by_default() {
+        let mut cache = lru_cache(2);
+        cache.put(1, 1);
+        cache.get(&1);
+        assert_eq!(cache.stats(), None);
+    }
+
+    #[test]
+    fn test_average_load_time() {
+        let stats = CacheStats {
+            load_successes: 1 << 32,
+            total_load_time: Duration::from_secs(1 << 32),
+            ..Default::default()
+        };
+        assert_eq!(stats.average_load_time(), Duration::from_secs(1));
+        assert_eq!(CacheStats::default().average_load_time(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_policy_stats() {
+        for (name, mut cache) in [
+            ("fifo", fifo_cache(2)),
+            ("lru", lru_cache(2)),
+            ("lfu", lfu_cache(2)),
+            ("arc", arc_cache(2)),
+            ("tinylfu", tinylfu_cache(2)),
+        ] {
+            cache.record_stats();
+            cache.put(1, "a");
+            cache.put(1, "b");
+            cache.get(&1);
+            cache.get(&9);
+            cache.put(2, "c");
+            cache.put(3, "d");
+            cache.remove(&3);
+
+            let stats = cache.stats().unwrap();
+            assert_eq!(stats.hits, 1, "{}", name);
+            assert_eq!(stats.misses, 1, "{}", name);
+            assert_eq!(stats.replaced, 1, "{}", name);
+            assert_eq!(
+                stats.capacity_evicted + stats.explicit_removed,
+                2,
+                "{} 容量淘汰 {:?}",
+                name,
+                stats
+            );
+            assert_eq!(cache.len(), 1, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_lru_stats() {
+        let mut cache = lru_cache(2);
+        cache.record_stats();
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+        assert!(cache.get(&"a").is_none());
+        assert_eq!(cache.remove(&"b"), Some(2));
+        assert_eq!(cache.remove(&"b"), None);
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.removals(RemovalCause::Capacity), 1);
+        assert_eq!(stats.removals(RemovalCause::Explicit), 1);
+        assert_eq!(stats.hit_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_builder_stats() {
+        let clock = Arc::new(MockClock::default());
+        let mut cache = CacheBuilder::new()
+            .max_entries(10)
+            .time_to_live(Duration::from_secs(5))
+            .clock(clock.clone())
+            .record_stats()
+            .build();
+        cache.put("a", 1);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(cache.get(&"a"), None);
+
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.hits, stats.misses, stats.expired), (1, 1, 1));
+        assert_eq!(stats.hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn test_concurrent_stats() {
+        let cache = concurrent_lru_cache(100);
+        cache.record_stats();
+        for i in 0..50 {
+            cache.put(i, i);
+        }
+        for i in 0..100 {
+            cache.get(&i);
+        }
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.requests(), 100);
+        assert_eq!(stats.hits, 50);
+    }
+
+    #[test]
+    fn test_loading_stats() {
+        let cache = LoadingCache::new(10).record_stats();
+        assert_eq!(cache.get_or_load("a", || Err("down")), Err("down"));
+        assert_eq!(cache.get_or_load("a", || Ok::<_, &str>(1)), Ok(1));
+        assert_eq!(cache.get_or_load("a", || Ok::<_, &str>(2)), Ok(1));
+        cache.get_or_insert_with("b", || {
+            std::thread::sleep(Duration::from_millis(20));
+            2
+        });
+
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+        assert_eq!((stats.load_successes, stats.load_failures), (2, 1));
+        assert!(stats.average_load_time() >= Duration::from_millis(20) / 3);
+    }
+
+    #[test]
+    fn test_empty_stats() {
+        let stats = CacheStats::default();
+        assert_eq!(stats.hit_rate(), 1.0);
+        assert_eq!(stats.average_load_time(), Duration::ZERO);
+    }
+}
@@ -29,4 +29,26 @@ mod tests {
             assert_eq!(lru_cache.get(&3), Some(&"three"))
         }
     }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Key(&'static str);
+
+    #[test]
+    fn test_lru_hash_keys() {
+        // 只需要 Hash + Eq，不再要求 Ord
+        let mut cache = lru_cache::<Key, i32>(2);
+        cache.put(Key("a"), 1);
+        cache.put(Key("b"), 2);
+        cache.put(Key("a"), 10); // 更新也算使用
+        cache.put(Key("c"), 3); // 淘汰 b
+        assert_eq!(cache.get(&Key("b")), None);
+        assert_eq!(cache.get(&Key("a")), Some(&10));
+        assert_eq!(cache.remove(&Key("c")), Some(3));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.capacity(), 2);
+
+        let mut empty = lru_cache::<Key, i32>(0);
+        empty.put(Key("a"), 1);
+        assert!(empty.is_empty(), "容量为 0 时不缓存");
+    }
 }
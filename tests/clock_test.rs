@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rovkit::cachekit::time_cache_with_clock;
+    use rovkit::clockkit::{Clock, MockClock, SystemClock};
+    use rovkit::datekit;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_mock_clock() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(
+            clock.now(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 1, 30).unwrap()
+        );
+
+        clock.set(start);
+        assert_eq!(
+            clock.now().timestamp(),
+            start.timestamp() + 90,
+            "时间不能回拨"
+        );
+        assert_eq!(clock.monotonic(), Duration::from_secs(90));
+    }
+
+    #[tokio::test]
+    async fn test_mock_sleep() {
+        let clock = Arc::new(MockClock::new(
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        ));
+        let woke = Arc::new(AtomicBool::new(false));
+        let sleeper = {
+            let (clock, woke) = (clock.clone(), woke.clone());
+            tokio::spawn(async move {
+                clock.sleep(Duration::from_secs(60)).await;
+                woke.store(true, Ordering::SeqCst);
+            })
+        };
+
+        clock.run_for(Duration::from_secs(59)).await;
+        assert!(!woke.load(Ordering::SeqCst));
+        assert_eq!(clock.sleepers(), 1);
+
+        clock.run_for(Duration::from_secs(1)).await;
+        assert!(woke.load(Ordering::SeqCst));
+        assert_eq!(clock.sleepers(), 0);
+        sleeper.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_system_sleep() {
+        let clock = SystemClock;
+        let start = clock.now();
+        clock.sleep(Duration::from_millis(50)).await;
+        assert!(clock.now() - start >= chrono::Duration::milliseconds(50));
+        clock.sleep_until(start).await;
+
+        let before = clock.monotonic();
+        clock.sleep(Duration::from_millis(20)).await;
+        assert!(clock.monotonic() - before >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_time_cache_with_clock() {
+        let clock = Arc::new(MockClock::default());
+        let mut cache = time_cache_with_clock(2, clock.clone());
+        cache.put("hello", "world");
+
+        clock.advance(Duration::from_millis(1999));
+        assert_eq!(cache.get(&"hello"), Some(&"world"));
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(cache.get(&"hello"), None, "到期后应失效");
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_datekit_now_with() {
+        let start = Utc.with_ymd_and_hms(2024, 2, 29, 12, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        assert_eq!(datekit::now_with(&clock).timestamp(), start.timestamp());
+    }
+}
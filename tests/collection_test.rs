@@ -5,6 +5,7 @@ mod tests {
     use std::thread;
 
     #[test]
+    #[allow(clippy::vec_init_then_push)]
     fn test_array_list() {
         {
             let mut list = ArrayList::<i32>::new();
@@ -29,7 +30,7 @@ mod tests {
 
         {
             let list = ArrayList::<i32>::from([1, 2, 3]);
-            assert_eq!(list.contains(&1), true);
+            assert!(list.contains(&1));
         }
     }
 
@@ -55,7 +56,7 @@ mod tests {
 
         {
             let list = LinkedList::<i32>::from([1, 2, 3]);
-            assert_eq!(list.contains(&1), true);
+            assert!(list.contains(&1));
         }
     }
 
@@ -441,8 +442,8 @@ mod tests {
             fn test_extend_and_contains() {
                 let mut q = Queue::from(vec![1, 2]);
                 q.extend([3, 4]);
-                assert_eq!(q.contains(&3), true);
-                assert_eq!(q.contains(&5), false);
+                assert!(q.contains(&3));
+                assert!(!q.contains(&5));
                 assert_eq!(q.len(), 4);
             }
         }
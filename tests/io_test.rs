@@ -75,8 +75,8 @@ mod tests {
         let link_path = dir.path().join("link.txt");
         create_symlink(&file_path, &link_path).unwrap();
 
-        assert_eq!(is_symlink(&file_path).unwrap(), false);
-        assert_eq!(is_symlink(&link_path).unwrap(), true);
+        assert!(!is_symlink(&file_path).unwrap());
+        assert!(is_symlink(&link_path).unwrap());
     }
 
     #[test]
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeZone, Utc};
+    use rovkit::clockkit::{Clock, MockClock};
+    use rovkit::jobkit::{JobKit, JobOptions, JobType, RetryPolicy};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 30).unwrap()
+    }
+
+    fn counter_job(
+        counter: &Arc<AtomicUsize>,
+    ) -> impl Fn() -> std::future::Ready<rovkit::jobkit::JobResult> + Send + Sync + 'static {
+        let counter = counter.clone();
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            std::future::ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interval_virtual_time() {
+        let clock = Arc::new(MockClock::new(start()));
+        let kit = JobKit::new(2).with_clock(clock.clone());
+        let counter = Arc::new(AtomicUsize::new(0));
+        kit.add_async_job(
+            "interval",
+            JobType::Interval(Duration::from_secs(10)),
+            counter_job(&counter),
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_secs(35)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 4);
+
+        let offsets: Vec<i64> = kit
+            .get_history("interval")
+            .unwrap()
+            .iter()
+            .map(|r| (r.timestamp - start()).num_seconds())
+            .collect();
+        assert_eq!(offsets, vec![0, 10, 20, 30]);
+        assert_eq!(
+            kit.status("interval").unwrap().next_run,
+            Some(start() + chrono::Duration::seconds(40))
+        );
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_cron_virtual_time() {
+        let clock = Arc::new(MockClock::new(start()));
+        let kit = JobKit::new(2).with_clock(clock.clone());
+        let counter = Arc::new(AtomicUsize::new(0));
+        kit.add_async_job(
+            "cron",
+            JobType::Cron("0 * * * * *".to_string()),
+            counter_job(&counter),
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_secs(29)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+        clock.run_for(Duration::from_secs(151)).await;
+        let minutes: Vec<String> = kit
+            .get_history("cron")
+            .unwrap()
+            .iter()
+            .map(|r| r.timestamp.format("%H:%M:%S").to_string())
+            .collect();
+        assert_eq!(minutes, vec!["00:01:00", "00:02:00", "00:03:00"]);
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_delay_and_retry_virtual_time() {
+        let clock = Arc::new(MockClock::new(start()));
+        let kit = JobKit::new(2).with_clock(clock.clone());
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let calls = attempts.clone();
+        kit.add_async_job_with_options(
+            "retry",
+            JobType::Delay(Duration::from_secs(60)),
+            JobOptions::new().retry(RetryPolicy::fixed(3, Duration::from_secs(5))),
+            move || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err("boom".into()) }
+            },
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_secs(62)).await;
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert!(kit.get_history("retry").unwrap().is_empty());
+
+        clock.run_for(Duration::from_secs(8)).await;
+        let history = kit.get_history("retry").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].attempts, 3);
+        assert_eq!(
+            history[0].timestamp,
+            start() + chrono::Duration::seconds(60)
+        );
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_timeout_virtual_time() {
+        let clock = Arc::new(MockClock::new(start()));
+        let kit = JobKit::new(2).with_clock(clock.clone());
+        let job_clock = clock.clone();
+        kit.add_async_job_with_options(
+            "slow",
+            JobType::At(start()),
+            JobOptions::new().timeout(Duration::from_secs(5)),
+            move || {
+                let sleep = job_clock.sleep(Duration::from_secs(10));
+                async move {
+                    sleep.await;
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_secs(4)).await;
+        assert!(kit.get_history("slow").unwrap().is_empty());
+
+        clock.run_for(Duration::from_secs(1)).await;
+        let history = kit.get_history("slow").unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(history[0].timed_out);
+        kit.stop_all();
+    }
+}
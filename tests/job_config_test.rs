@@ -0,0 +1,305 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::jobkit::{
+        Backoff, JobConfig, JobKit, JobLoader, JobRecord, JobRegistry, JobStore, JobTimeZone,
+        JobType, StoredJob,
+    };
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const CONFIG: &str = r#"{
+        "jobs": [
+            {
+                "name": "report",
+                "job_type": { "Cron": "0 0 8 * * *" },
+                "timezone": "+08:00",
+                "timeout": "1m 30s",
+                "retry": {
+                    "max_attempts": 3,
+                    "backoff": { "Fixed": 1 },
+                    "jitter": false
+                }
+            },
+            {
+                "name": "cleanup",
+                "handler": "noop",
+                "job_type": { "Interval": "60s" },
+                "enabled": false
+            }
+        ]
+    }"#;
+
+    fn registry(counter: Arc<AtomicUsize>) -> JobRegistry {
+        JobRegistry::new()
+            .register("noop", || Ok(()))
+            .register("report", || Ok(()))
+            .register("count", move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .register_async("async_noop", || async { Ok(()) })
+    }
+
+    fn write(path: &Path, jobs: &[(&str, &str, u64)]) {
+        let jobs: Vec<String> = jobs
+            .iter()
+            .map(|(name, handler, millis)| {
+                format!(
+                    r#"{{"name":"{}","handler":"{}","job_type":{{"Interval":"{}ms"}}}}"#,
+                    name, handler, millis
+                )
+            })
+            .collect();
+        fs::write(path, format!(r#"{{"jobs":[{}]}}"#, jobs.join(","))).unwrap();
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        fs::write(&path, CONFIG).unwrap();
+
+        let config = JobConfig::from_file(&path).unwrap();
+        assert_eq!(config.jobs.len(), 2);
+        let report = &config.jobs[0];
+        assert!(report.enabled);
+        assert!(report.handler.is_none());
+        assert_eq!(report.options.timezone, JobTimeZone::hours(8).unwrap());
+        assert_eq!(report.options.timeout, Some(Duration::from_secs(90)));
+        let retry = report.options.retry.unwrap();
+        assert_eq!(retry.max_attempts, 3);
+        assert_eq!(retry.backoff, Backoff::Fixed(Duration::from_secs(1)));
+        assert!(!config.jobs[1].enabled);
+        assert!(matches!(
+            config.jobs[1].job_type,
+            JobType::Interval(d) if d == Duration::from_secs(60)
+        ));
+
+        assert!(JobConfig::from_file(dir.path().join("missing.json")).is_err());
+    }
+
+    fn parse(json: &str) -> Result<JobConfig, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_config_formats() {
+        // 存储使用的序列化格式仍然可读
+        let config = parse(
+            r#"{"jobs":[{"name":"a","job_type":{"Interval":{"secs":0,"nanos":500000000}},
+                "timezone":{"Fixed":-18000},"timeout":null}]}"#,
+        )
+        .unwrap();
+        let job = &config.jobs[0];
+        assert!(matches!(job.job_type, JobType::Interval(d) if d == Duration::from_millis(500)));
+        assert_eq!(job.options.timezone, JobTimeZone::hours(-5).unwrap());
+        assert_eq!(job.options.timeout, None);
+
+        for (zone, expected) in [
+            ("UTC", JobTimeZone::Utc),
+            ("Local", JobTimeZone::Local),
+            ("UTC+8", JobTimeZone::hours(8).unwrap()),
+            ("-0530", JobTimeZone::Fixed(-19800)),
+        ] {
+            assert_eq!(zone.parse::<JobTimeZone>().unwrap(), expected, "{}", zone);
+        }
+        for zone in ["+25:00", "8", "+08:60", "Asia/Shanghai"] {
+            assert!(zone.parse::<JobTimeZone>().is_err(), "{}", zone);
+        }
+
+        for (json, error) in [
+            (r#"{"jobs":[{"name":"a","job_type":{"Delay":"5x"}}]}"#, "Invalid duration"),
+            (r#"{"jobs":[{"name":"a","job_type":{"Delay":-1}}]}"#, "Invalid duration"),
+            (r#"{"jobs":[{"name":"a","job_type":{"Delay":1},"timout":"5s"}]}"#, "timout"),
+            (
+                r#"{"jobs":[{"name":"a","job_type":{"Delay":1},"retry":{"max_attempt":3}}]}"#,
+                "max_attempt",
+            ),
+            (r#"{"job":[]}"#, "job"),
+            (
+                r#"{"jobs":[{"name":"a","job_type":{"Delay":1},"timezone":{"Fixed":90000}}]}"#,
+                "out of range",
+            ),
+        ] {
+            let err = parse(json).unwrap_err();
+            assert!(err.contains(error), "{} -> {}", json, err);
+        }
+    }
+
+    /// `load_jobs` 在第 `fail_at` 次调用时失败
+    struct FlakyStore {
+        calls: AtomicUsize,
+        fail_at: AtomicUsize,
+    }
+
+    impl JobStore for FlakyStore {
+        fn save_job(&self, _: &StoredJob) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn load_jobs(&self) -> io::Result<Vec<StoredJob>> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            match call == self.fail_at.load(Ordering::SeqCst) {
+                true => Err(io::Error::other("store down")),
+                false => Ok(Vec::new()),
+            }
+        }
+
+        fn remove_job(&self, _: &str) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn append_record(&self, _: &str, _: &JobRecord) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn load_history(&self, _: &str) -> io::Result<Vec<JobRecord>> {
+            Ok(Vec::new())
+        }
+
+        fn save_history(&self, _: &str, _: &[JobRecord]) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_rolls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        let store = Arc::new(FlakyStore {
+            calls: AtomicUsize::new(0),
+            fail_at: AtomicUsize::new(0),
+        });
+        let kit = JobKit::with_store(2, store.clone());
+        let loader = JobLoader::new(&path, registry(Arc::new(AtomicUsize::new(0))));
+        write(&path, &[("a", "noop", 500), ("b", "noop", 500)]);
+        loader.load(&kit).unwrap();
+
+        // 更新 a 成功，添加 c 失败：a 恢复原定义，b 不删除
+        write(&path, &[("a", "count", 500), ("c", "noop", 500)]);
+        store
+            .fail_at
+            .store(store.calls.load(Ordering::SeqCst) + 2, Ordering::SeqCst);
+        let err = loader.load(&kit).unwrap_err();
+        assert!(err.contains("store down"), "{}", err);
+        assert!(kit.status("a").is_some());
+        assert!(kit.status("b").is_some(), "失败时不应删除任务");
+        assert!(kit.status("c").is_none());
+
+        let report = loader.load(&kit).unwrap();
+        assert_eq!(report.added, vec!["c"]);
+        assert_eq!(report.updated, vec!["a"], "回滚后 a 仍是原定义");
+        assert_eq!(report.removed, vec!["b"]);
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_apply_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        fs::write(&path, CONFIG).unwrap();
+
+        let kit = JobKit::new(2);
+        kit.add_job("manual", JobType::Interval(Duration::from_secs(60)), || {
+            Ok(())
+        })
+        .unwrap();
+        let loader = JobLoader::new(&path, registry(Arc::new(AtomicUsize::new(0))));
+
+        let report = loader.load(&kit).unwrap();
+        assert_eq!(report.added, vec!["report"]);
+        assert!(kit.status("report").is_some());
+        assert!(kit.status("cleanup").is_none(), "禁用的任务不应添加");
+
+        let report = loader.load(&kit).unwrap();
+        assert!(report.added.is_empty() && report.updated.is_empty());
+
+        write(
+            &path,
+            &[("report", "noop", 500), ("cleanup", "async_noop", 500)],
+        );
+        let report = loader.load(&kit).unwrap();
+        assert_eq!(report.added, vec!["cleanup"]);
+        assert_eq!(report.updated, vec!["report"]);
+
+        write(&path, &[("cleanup", "async_noop", 500)]);
+        let report = loader.load(&kit).unwrap();
+        assert_eq!(report.removed, vec!["report"]);
+        assert!(kit.status("report").is_none());
+        assert!(kit.status("manual").is_some(), "代码添加的任务不受配置影响");
+
+        write(
+            &path,
+            &[("cleanup", "missing", 500), ("other", "noop", 500)],
+        );
+        let err = loader.load(&kit).unwrap_err();
+        assert!(err.contains("Unknown handler"), "{}", err);
+        assert!(kit.status("cleanup").is_some());
+        assert!(kit.status("other").is_none(), "无效配置不应改动任务");
+
+        // 和代码添加的任务重名
+        write(&path, &[("other", "noop", 500), ("manual", "noop", 500)]);
+        let err = loader.load(&kit).unwrap_err();
+        assert!(err.contains("already exists"), "{}", err);
+        assert!(kit.status("other").is_none(), "重名时不应添加其他任务");
+        assert!(kit.status("cleanup").is_some(), "重名时不应删除任务");
+
+        write(&path, &[("other", "noop", 500)]);
+        let report = loader.load(&kit).unwrap();
+        assert_eq!(report.added, vec!["other"]);
+        assert_eq!(report.removed, vec!["cleanup"]);
+
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_hot_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        write(&path, &[("first", "noop", 1000)]);
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        let kit = Arc::new(JobKit::new(2));
+        let loader = Arc::new(JobLoader::new(&path, registry(counter.clone())));
+        loader.load(&kit).unwrap();
+        let watcher = loader.watch(kit.clone(), Duration::from_millis(20));
+
+        write(&path, &[("second", "count", 20)]);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(kit.status("first").is_none());
+        assert!(kit.status("second").is_some());
+        assert!(counter.load(Ordering::SeqCst) > 0);
+
+        fs::write(&path, "not json").unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(kit.status("second").is_some(), "坏文件应保留现有任务");
+
+        watcher.abort();
+        kit.stop_all();
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.yaml");
+        fs::write(
+            &path,
+            "jobs:\n  - name: report\n    job_type: !Cron \"0 0 8 * * *\"\n    timezone: Local\n  \
+             - name: poll\n    job_type: !Interval 30s\n    timezone: \"+08:00\"\n    timeout: 5\n",
+        )
+        .unwrap();
+
+        let config = JobConfig::from_file(&path).unwrap();
+        assert_eq!(config.jobs[0].name, "report");
+        assert_eq!(config.jobs[0].options.timezone, JobTimeZone::Local);
+        let poll = &config.jobs[1];
+        assert!(matches!(poll.job_type, JobType::Interval(d) if d == Duration::from_secs(30)));
+        assert_eq!(poll.options.timezone, JobTimeZone::hours(8).unwrap());
+        assert_eq!(poll.options.timeout, Some(Duration::from_secs(5)));
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration as ChronoDuration, TimeZone, Utc};
+    use rovkit::jobkit::{
+        HistoryQuery, JobHistory, JobKit, JobOptions, JobRecord, JobType, RecordFilter,
+    };
+    use std::time::Duration;
+
+    fn record(minute: u32, millis: u64, ok: bool) -> JobRecord {
+        JobRecord {
+            name: "stats_job".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, minute, 0).unwrap(),
+            duration: Duration::from_millis(millis),
+            result: if ok { Ok(()) } else { Err("boom".to_string()) },
+            attempts: 1,
+            timed_out: false,
+            skipped: false,
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        let mut history = JobHistory::new(3);
+        for i in 0..5 {
+            history.push(record(i, 10, true));
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.capacity(), 3);
+        let minutes: Vec<_> = history
+            .iter()
+            .map(|r| r.timestamp.format("%M").to_string())
+            .collect();
+        assert_eq!(minutes, vec!["02", "03", "04"]);
+    }
+
+    #[test]
+    fn test_query() {
+        let mut history = JobHistory::new(100);
+        for i in 0..10 {
+            history.push(record(i, 10, i % 3 != 0));
+        }
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 2, 0).unwrap();
+        let end = start + ChronoDuration::minutes(5);
+        let ranged = history.query(&HistoryQuery::new().since(start).until(end));
+        assert_eq!(ranged.len(), 5);
+        assert_eq!(ranged[0].timestamp, start);
+
+        let failures = history.query(&HistoryQuery::new().filter(RecordFilter::Failure));
+        assert_eq!(failures.len(), 4);
+
+        let last = history.last_failures(2);
+        assert_eq!(last.len(), 2);
+        assert_eq!(last[0].timestamp.format("%M").to_string(), "06");
+        assert_eq!(last[1].timestamp.format("%M").to_string(), "09");
+
+        let successes = history.query(
+            &HistoryQuery::new()
+                .filter(RecordFilter::Success)
+                .since(start)
+                .limit(1),
+        );
+        assert_eq!(successes[0].timestamp.format("%M").to_string(), "08");
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut history = JobHistory::new(100);
+        assert_eq!(history.stats().runs, 0);
+
+        for millis in 1..=18 {
+            history.push(record(millis as u32, millis * 10, true));
+        }
+        history.push(record(20, 1000, false));
+        history.push(record(21, 190, false));
+        let mut skipped = record(22, 0, false);
+        skipped.skipped = true;
+        history.push(skipped);
+
+        let stats = history.stats();
+        assert_eq!(stats.runs, 20);
+        assert_eq!(stats.successes, 18);
+        assert_eq!(stats.failures, 2);
+        assert_eq!(stats.skipped, 1);
+        assert!((stats.success_rate - 0.9).abs() < f64::EPSILON);
+        assert_eq!(stats.p50_duration, Duration::from_millis(100));
+        assert_eq!(stats.p95_duration, Duration::from_millis(190));
+        assert_eq!(stats.max_duration, Duration::from_secs(1));
+        assert_eq!(stats.consecutive_failures, 2);
+        assert!(stats.last_success.unwrap() < stats.last_failure.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_job_history_capacity() {
+        let kit = JobKit::new(2);
+        kit.add_job_with_options(
+            "capped_job",
+            JobType::Interval(Duration::from_millis(10)),
+            JobOptions::new().history_capacity(5),
+            || Err("always".into()),
+        )
+        .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(kit.get_history("capped_job").unwrap().len(), 5);
+
+        let stats = kit.stats("capped_job").unwrap();
+        assert_eq!(stats.runs, 5);
+        assert_eq!(stats.success_rate, 0.0);
+        assert_eq!(stats.consecutive_failures, 5);
+        assert_eq!(kit.last_failures("capped_job", 2).unwrap().len(), 2);
+        assert!(kit
+            .query_history(
+                "capped_job",
+                &HistoryQuery::new().filter(RecordFilter::Success)
+            )
+            .unwrap()
+            .is_empty());
+        assert!(kit.stats("missing").is_none());
+        kit.stop_all();
+    }
+}
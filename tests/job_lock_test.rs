@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    use rovkit::jobkit::FileJobLock;
+    use rovkit::jobkit::{
+        JobKit, JobLock, JobOptions, JobType, LeaseJobLock, MemoryLeaseStore, SingletonPolicy,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_lease_lock() {
+        let store = Arc::new(MemoryLeaseStore::new());
+        let a = LeaseJobLock::with_owner(store.clone(), "a");
+        let b = LeaseJobLock::with_owner(store.clone(), "b");
+        let ttl = Duration::from_millis(50);
+
+        assert!(a.try_lock("job", ttl).unwrap());
+        assert!(!a.try_lock("job", ttl).unwrap(), "同一实例也不能重复持有");
+        assert!(!b.try_lock("job", ttl).unwrap());
+        assert!(b.try_lock("other", ttl).unwrap());
+
+        b.unlock("job").unwrap();
+        assert_eq!(store.owner("job").as_deref(), Some("a"), "非持有者不能释放");
+
+        std::thread::sleep(Duration::from_millis(80));
+        assert!(store.owner("job").is_none(), "租约应已过期");
+        assert!(b.try_lock("job", ttl).unwrap());
+
+        b.unlock("job").unwrap();
+        assert!(a.try_lock("job", ttl).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = FileJobLock::new(dir.path()).unwrap();
+        let b = FileJobLock::new(dir.path()).unwrap();
+        let ttl = Duration::from_secs(1);
+
+        assert!(a.try_lock("wf/step", ttl).unwrap());
+        assert!(!b.try_lock("wf/step", ttl).unwrap());
+        assert!(!a.try_lock("wf/step", ttl).unwrap());
+        assert!(dir.path().join("wf_step.lock").exists());
+
+        a.unlock("wf/step").unwrap();
+        assert!(b.try_lock("wf/step", ttl).unwrap());
+        drop(b);
+        assert!(
+            a.try_lock("wf/step", ttl).unwrap(),
+            "实例释放后锁应被系统回收"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_singleton_job() {
+        let store = Arc::new(MemoryLeaseStore::new());
+        let runs = Arc::new(AtomicUsize::new(0));
+        let options = JobOptions::new().singleton(
+            SingletonPolicy::new(Duration::from_secs(5)).lock_at_least(Duration::from_millis(100)),
+        );
+
+        let kits: Vec<JobKit> = (0..3)
+            .map(|_| {
+                let kit = JobKit::new(2);
+                kit.set_lock(Arc::new(LeaseJobLock::new(store.clone())));
+                let runs = runs.clone();
+                kit.add_job_with_options(
+                    "singleton_job",
+                    JobType::Interval(Duration::from_millis(200)),
+                    options.clone(),
+                    move || {
+                        runs.fetch_add(1, Ordering::SeqCst);
+                        Ok(())
+                    },
+                )
+                .unwrap();
+                kit
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_millis(700)).await;
+        let total = runs.load(Ordering::SeqCst);
+        assert!(
+            (3..=5).contains(&total),
+            "每个周期只应执行一次, total = {}",
+            total
+        );
+
+        for kit in &kits {
+            kit.stop_all();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_singleton_without_lock() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let kit = JobKit::new(2);
+        let counter = runs.clone();
+        kit.add_job_with_options(
+            "no_lock_job",
+            JobType::Interval(Duration::from_millis(50)),
+            JobOptions::new().singleton(SingletonPolicy::new(Duration::from_secs(1))),
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(180)).await;
+        assert!(runs.load(Ordering::SeqCst) >= 2);
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_stop_releases_lock() {
+        let store = Arc::new(MemoryLeaseStore::new());
+        let kit = JobKit::new(1);
+        kit.set_lock(Arc::new(LeaseJobLock::new(store.clone())));
+        kit.add_async_job_with_options(
+            "long_job",
+            JobType::Interval(Duration::from_secs(60)),
+            JobOptions::new().singleton(SingletonPolicy::new(Duration::from_secs(60))),
+            || async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(store.owner("long_job").is_some());
+        // 运行中被停止，锁也要释放
+        kit.stop("long_job");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(store.owner("long_job").is_none());
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration as ChronoDuration, Utc};
+    use rovkit::jobkit::{
+        FileJobStore, JobKit, JobOptions, JobRecord, JobStore, JobType, MisfirePolicy, StoredJob,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn record(name: &str, ok: bool) -> JobRecord {
+        JobRecord {
+            name: name.to_string(),
+            timestamp: Utc::now(),
+            duration: Duration::from_millis(5),
+            result: if ok { Ok(()) } else { Err("boom".to_string()) },
+            attempts: 1,
+            timed_out: false,
+            skipped: false,
+        }
+    }
+
+    fn stored_yearly(name: &str) -> StoredJob {
+        StoredJob {
+            id: format!("{}-id", name),
+            name: name.to_string(),
+            job_type: JobType::Cron("0 0 0 1 1 *".into()),
+            options: JobOptions::new(),
+            created_at: Utc::now() - ChronoDuration::days(800),
+            last_run: None,
+        }
+    }
+
+    #[test]
+    fn test_file_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileJobStore::new(dir.path()).unwrap();
+
+        let job = stored_yearly("store_job");
+        store.save_job(&job).unwrap();
+        store.save_job(&job).unwrap();
+        assert_eq!(store.load_jobs().unwrap().len(), 1);
+
+        store
+            .append_record(&job.id, &record("store_job", true))
+            .unwrap();
+        store
+            .append_record(&job.id, &record("store_job", false))
+            .unwrap();
+        let history = store.load_history(&job.id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history[0].result.is_ok());
+        assert_eq!(history[1].result, Err("boom".to_string()));
+
+        // 重新打开后数据仍在
+        let store = FileJobStore::new(dir.path()).unwrap();
+        assert_eq!(store.load_jobs().unwrap()[0].name, "store_job");
+
+        store.remove_job(&job.id).unwrap();
+        assert!(store.load_jobs().unwrap().is_empty());
+        assert!(store.load_history(&job.id).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_history_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let store: Arc<dyn JobStore> = Arc::new(FileJobStore::new(dir.path()).unwrap());
+
+        let kit = JobKit::with_store(2, store.clone());
+        let id = kit
+            .add_job(
+                "persist_job",
+                JobType::Interval(Duration::from_millis(50)),
+                || Ok(()),
+            )
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let count = kit.get_history(&id).unwrap().len();
+        kit.stop_all();
+        assert!(count >= 2);
+
+        let kit = JobKit::with_store(2, store.clone());
+        let new_id = kit
+            .add_job(
+                "persist_job",
+                JobType::Interval(Duration::from_secs(3600)),
+                || Ok(()),
+            )
+            .unwrap();
+        assert_eq!(new_id, id, "同名任务应复用持久化的 id");
+
+        let history = kit.get_history(&id).unwrap();
+        assert!(history.len() >= count);
+        assert!(kit.status(&id).unwrap().last_run.is_some());
+
+        assert!(kit.remove(&id));
+        assert!(store.load_jobs().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_misfire_fire_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let store: Arc<dyn JobStore> = Arc::new(FileJobStore::new(dir.path()).unwrap());
+        store.save_job(&stored_yearly("misfire_job")).unwrap();
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+        let kit = JobKit::with_store(2, store.clone());
+        kit.add_job_with_options(
+            "misfire_job",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            JobOptions::new().misfire(MisfirePolicy::FireOnce),
+            move || {
+                counter_clone.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 1, "错过的触发应补跑一次");
+        let stored = store.load_jobs().unwrap();
+        assert_eq!(store.load_history(&stored[0].id).unwrap().len(), 1);
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_history_file_compacted() {
+        let dir = tempfile::tempdir().unwrap();
+        let store: Arc<dyn JobStore> = Arc::new(FileJobStore::new(dir.path()).unwrap());
+
+        let kit = JobKit::with_store(2, store.clone());
+        let id = kit
+            .add_job_with_options(
+                "busy_job",
+                JobType::Interval(Duration::from_millis(10)),
+                JobOptions::new().history_capacity(3),
+                || Ok(()),
+            )
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        kit.stop_all();
+        // 超过两倍容量时压缩回容量
+        assert!(store.load_history(&id).unwrap().len() <= 6);
+        assert!(store.load_jobs().unwrap()[0].last_run.is_some());
+
+        let kit = JobKit::with_store(2, store.clone());
+        kit.add_job_with_options(
+            "busy_job",
+            JobType::Interval(Duration::from_secs(3600)),
+            JobOptions::new().history_capacity(3),
+            || Ok(()),
+        )
+        .unwrap();
+        assert_eq!(kit.get_history(&id).unwrap().len(), 3);
+        assert!(kit.status(&id).unwrap().last_run.is_some());
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_misfire_ignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let store: Arc<dyn JobStore> = Arc::new(FileJobStore::new(dir.path()).unwrap());
+        store.save_job(&stored_yearly("ignored_job")).unwrap();
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+        let kit = JobKit::with_store(2, store);
+        kit.add_job(
+            "ignored_job",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            move || {
+                counter_clone.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+        kit.stop_all();
+    }
+}
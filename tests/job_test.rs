@@ -1,6 +1,13 @@
 #[cfg(test)]
 mod tests {
-    use rovkit::jobkit::{JobKit, JobType, MAX_HISTORY_RECORDS};
+    use chrono::{DateTime, TimeZone, Utc};
+    use rovkit::clockkit::{Clock, MockClock};
+    use rovkit::jobkit::{
+        JobKit, JobListener, JobOptions, JobRecord, JobResult, JobState, JobTimeZone, JobType,
+        OverlapPolicy, RetryPolicy, MAX_HISTORY_RECORDS,
+    };
+    use std::future::Future;
+    use std::pin::Pin;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
     use std::time::Duration;
@@ -9,6 +16,17 @@ mod tests {
         let _ = env_logger::builder().is_test(true).try_init();
     }
 
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 30).unwrap()
+    }
+
+    /// 调度走虚拟时钟的 JobKit
+    fn mock_kit(max_concurrent: usize) -> (Arc<MockClock>, JobKit) {
+        let clock = Arc::new(MockClock::new(start()));
+        let kit = JobKit::new(max_concurrent).with_clock(clock.clone());
+        (clock, kit)
+    }
+
     #[tokio::test]
     async fn test_interval_job_execution() {
         init_logger();
@@ -142,4 +160,774 @@ mod tests {
         assert!(history.len() <= MAX_HISTORY_RECORDS);
         kit.stop("fast_job");
     }
+
+    /// 记录并发峰值的慢任务
+    fn slow_task(
+        current: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+        runs: Arc<AtomicUsize>,
+        cost: Duration,
+    ) -> impl Fn() -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync + 'static
+    {
+        move || {
+            let now = current.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(cost);
+            current.fetch_sub(1, Ordering::SeqCst);
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_max_concurrent_tasks() {
+        init_logger();
+        let kit = JobKit::new(1);
+        let current = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        for name in ["limit_a", "limit_b"] {
+            kit.add_job_with_options(
+                name,
+                JobType::Interval(Duration::from_millis(50)),
+                JobOptions::new().overlap(OverlapPolicy::Allow),
+                slow_task(
+                    current.clone(),
+                    peak.clone(),
+                    runs.clone(),
+                    Duration::from_millis(100),
+                ),
+            )
+            .unwrap();
+        }
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        kit.stop_all();
+
+        assert!(runs.load(Ordering::SeqCst) >= 2);
+        assert_eq!(peak.load(Ordering::SeqCst), 1, "全局并发上限应为 1");
+    }
+
+    /// `slow_task` 的异步版本，在虚拟时钟上耗时 `cost`
+    fn slow_async_task(
+        clock: &Arc<MockClock>,
+        current: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+        runs: Arc<AtomicUsize>,
+        cost: Duration,
+    ) -> impl Fn() -> Pin<Box<dyn Future<Output = JobResult> + Send>> + Send + Sync + 'static {
+        let clock = clock.clone();
+        move || {
+            let (current, peak, runs) = (current.clone(), peak.clone(), runs.clone());
+            let sleep = clock.sleep(cost);
+            Box::pin(async move {
+                let now = current.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                sleep.await;
+                current.fetch_sub(1, Ordering::SeqCst);
+                runs.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlap_skip() {
+        init_logger();
+        let (clock, kit) = mock_kit(4);
+        let current = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        kit.add_async_job_with_options(
+            "skip_job",
+            JobType::Interval(Duration::from_secs(1)),
+            JobOptions::new().overlap(OverlapPolicy::Skip),
+            slow_async_task(
+                &clock,
+                current.clone(),
+                peak.clone(),
+                runs.clone(),
+                Duration::from_millis(2500),
+            ),
+        )
+        .unwrap();
+
+        // 0、3、6 秒的执行各自跳过之后两次 tick
+        clock.run_for(Duration::from_secs(9)).await;
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 3, "重叠的 tick 应被跳过");
+        let offsets: Vec<i64> = kit
+            .get_history("skip_job")
+            .unwrap()
+            .iter()
+            .map(|r| (r.timestamp - start()).num_seconds())
+            .collect();
+        assert_eq!(offsets, vec![0, 3, 6]);
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_overlap_allow() {
+        init_logger();
+        let (clock, kit) = mock_kit(4);
+        let current = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        kit.add_async_job_with_options(
+            "allow_job",
+            JobType::Interval(Duration::from_secs(1)),
+            JobOptions::new().overlap(OverlapPolicy::Allow),
+            slow_async_task(
+                &clock,
+                current.clone(),
+                peak.clone(),
+                runs.clone(),
+                Duration::from_millis(2500),
+            ),
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_secs(5)).await;
+        assert_eq!(peak.load(Ordering::SeqCst), 3, "允许并发时应出现重叠执行");
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        init_logger();
+        let (clock, kit) = mock_kit(2);
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        kit.add_async_job(
+            "pause_job",
+            JobType::Interval(Duration::from_secs(1)),
+            counting_task(&counter),
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_millis(3500)).await;
+        assert!(kit.pause("pause_job"));
+
+        let status = kit.status("pause_job").unwrap();
+        assert_eq!(status.state, JobState::Paused);
+        assert_eq!(
+            status.last_run,
+            Some(start() + chrono::Duration::seconds(3))
+        );
+        assert!(status.next_run.is_none());
+        assert_eq!(counter.load(Ordering::SeqCst), 4);
+
+        clock.run_for(Duration::from_secs(3)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 4, "暂停后不应再执行");
+        assert_eq!(kit.get_history("pause_job").unwrap().len(), 4);
+
+        // 恢复后按原来的节奏继续，暂停期间的 tick 不补执行
+        assert!(kit.resume("pause_job"));
+        clock.run_for(Duration::from_secs(2)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 6, "恢复后应继续执行");
+        assert_ne!(kit.status("pause_job").unwrap().state, JobState::Paused);
+
+        assert!(!kit.pause("missing_job"));
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_trigger_now() {
+        init_logger();
+        let (clock, kit) = mock_kit(2);
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let id = kit
+            .add_async_job(
+                "yearly_job",
+                JobType::Cron("0 0 0 1 1 *".into()),
+                counting_task(&counter),
+            )
+            .unwrap();
+
+        clock.run_for(Duration::from_secs(1)).await;
+        let status = kit.status(&id).unwrap();
+        assert_eq!(status.name, "yearly_job");
+        assert_eq!(status.state, JobState::Idle);
+        assert!(status.last_run.is_none());
+        assert_eq!(
+            status.next_run,
+            Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+        );
+
+        assert!(kit.pause("yearly_job"));
+        assert!(kit.trigger_now("yearly_job"));
+        clock.run_for(Duration::from_secs(1)).await;
+
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert_eq!(kit.get_history(&id).unwrap().len(), 1);
+        assert_eq!(
+            kit.status(&id).unwrap().last_run,
+            Some(start() + chrono::Duration::seconds(1))
+        );
+        assert!(!kit.trigger_now("missing_job"));
+        kit.stop_all();
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let fixed = RetryPolicy::fixed(3, Duration::from_millis(100));
+        assert_eq!(fixed.delay(1), Duration::from_millis(100));
+        assert_eq!(fixed.delay(5), Duration::from_millis(100));
+
+        let exp = RetryPolicy::exponential(5, Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(exp.delay(1), Duration::from_millis(100));
+        assert_eq!(exp.delay(2), Duration::from_millis(200));
+        assert_eq!(exp.delay(3), Duration::from_millis(400));
+        assert_eq!(exp.delay(10), Duration::from_secs(1));
+
+        let jitter = exp.with_jitter();
+        for _ in 0..100 {
+            let d = jitter.delay(3);
+            assert!(d >= Duration::from_millis(200) && d <= Duration::from_millis(400));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success() {
+        init_logger();
+        let (clock, kit) = mock_kit(2);
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+
+        kit.add_async_job_with_options(
+            "flaky_job",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            JobOptions::new().retry(RetryPolicy::fixed(3, Duration::from_secs(1))),
+            move || {
+                let result: JobResult = if counter_clone.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err("network error".into())
+                } else {
+                    Ok(())
+                };
+                std::future::ready(result)
+            },
+        )
+        .unwrap();
+
+        kit.trigger_now("flaky_job");
+        clock.run_for(Duration::from_millis(1500)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+        assert!(kit.get_history("flaky_job").unwrap().is_empty());
+
+        clock.run_for(Duration::from_secs(1)).await;
+        let history = kit.get_history("flaky_job").unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(history[0].result.is_ok());
+        assert_eq!(history[0].attempts, 3);
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_retry_exhausted() {
+        init_logger();
+        let (clock, kit) = mock_kit(2);
+
+        kit.add_async_job_with_options(
+            "always_fail",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            JobOptions::new().retry(RetryPolicy::exponential(
+                3,
+                Duration::from_secs(1),
+                Duration::from_secs(5),
+            )),
+            || std::future::ready(Err("boom".into())),
+        )
+        .unwrap();
+
+        // 重试间隔 1 秒、2 秒
+        kit.trigger_now("always_fail");
+        clock.run_for(Duration::from_millis(2500)).await;
+        assert!(kit.get_history("always_fail").unwrap().is_empty());
+
+        clock.run_for(Duration::from_secs(1)).await;
+        let history = kit.get_history("always_fail").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].result, Err("boom".to_string()));
+        assert_eq!(history[0].attempts, 3);
+        assert!(!history[0].timed_out);
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_run_timeout() {
+        init_logger();
+        let kit = JobKit::new(2);
+
+        kit.add_job_with_options(
+            "slow_job",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            JobOptions::new().timeout(Duration::from_millis(100)),
+            || {
+                std::thread::sleep(Duration::from_millis(500));
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        kit.trigger_now("slow_job");
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        // 同步任务无法中断，返回后才记录超时
+        assert!(kit.get_history("slow_job").unwrap().is_empty());
+        assert_eq!(kit.status("slow_job").unwrap().state, JobState::Running);
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        let history = kit.get_history("slow_job").unwrap();
+        assert_eq!(history.len(), 1);
+        assert!(history[0].timed_out);
+        assert!(history[0].result.is_err());
+        assert!(history[0].duration >= Duration::from_millis(500));
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_sync_timeout_no_overlap() {
+        init_logger();
+        let kit = JobKit::new(4);
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (active_clone, peak_clone) = (active.clone(), peak.clone());
+
+        kit.add_job_with_options(
+            "stuck_job",
+            JobType::Interval(Duration::from_millis(20)),
+            JobOptions::new()
+                .timeout(Duration::from_millis(20))
+                .retry(RetryPolicy::fixed(2, Duration::ZERO)),
+            move || {
+                let now = active_clone.fetch_add(1, Ordering::SeqCst) + 1;
+                peak_clone.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(100));
+                active_clone.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let history = kit.get_history("stuck_job").unwrap();
+        kit.stop_all();
+        assert_eq!(peak.load(Ordering::SeqCst), 1, "重试和下一次调度不应与超时的任务重叠");
+        assert!(!history.is_empty());
+        assert!(history.iter().all(|r| r.timed_out && r.attempts == 2));
+    }
+
+    #[tokio::test]
+    async fn test_async_job() {
+        init_logger();
+        let (clock, kit) = mock_kit(2);
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+        let job_clock = clock.clone();
+
+        kit.add_async_job(
+            "async_job",
+            JobType::Interval(Duration::from_secs(1)),
+            move || {
+                let counter = counter_clone.clone();
+                let sleep = job_clock.sleep(Duration::from_millis(100));
+                async move {
+                    sleep.await;
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_millis(3500)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 4);
+        let history = kit.get_history("async_job").unwrap();
+        assert!(history.iter().all(|r| r.result.is_ok()));
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_async_job_failure_and_timeout() {
+        init_logger();
+        let (clock, kit) = mock_kit(2);
+        let finished = Arc::new(AtomicUsize::new(0));
+        let finished_clone = finished.clone();
+        let job_clock = clock.clone();
+
+        kit.add_async_job(
+            "async_fail",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            || async { Err("async error".into()) },
+        )
+        .unwrap();
+        kit.add_async_job(
+            "async_panic",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            || async { panic!("async panic") },
+        )
+        .unwrap();
+        kit.add_async_job_with_options(
+            "async_slow",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            JobOptions::new().timeout(Duration::from_secs(1)),
+            move || {
+                let finished = finished_clone.clone();
+                let sleep = job_clock.sleep(Duration::from_secs(5));
+                async move {
+                    sleep.await;
+                    finished.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+
+        for name in ["async_fail", "async_panic", "async_slow"] {
+            kit.trigger_now(name);
+        }
+        clock.run_for(Duration::from_secs(1)).await;
+
+        let fail = kit.get_history("async_fail").unwrap();
+        assert_eq!(fail[0].result, Err("async error".to_string()));
+        let panic = kit.get_history("async_panic").unwrap();
+        assert!(panic[0].result.is_err());
+        let slow = kit.get_history("async_slow").unwrap();
+        assert!(slow[0].timed_out);
+
+        clock.run_for(Duration::from_secs(5)).await;
+        assert_eq!(finished.load(Ordering::SeqCst), 0, "超时的异步任务应被取消");
+        kit.stop_all();
+    }
+
+    #[derive(Default)]
+    struct RecordingListener {
+        events: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl JobListener for RecordingListener {
+        fn on_start(&self, name: &str, _time: chrono::DateTime<chrono::Utc>) {
+            self.events.lock().unwrap().push(format!("start:{}", name));
+        }
+
+        fn on_success(&self, record: &JobRecord) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("success:{}", record.name));
+        }
+
+        fn on_failure(&self, record: &JobRecord) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("failure:{}", record.name));
+        }
+
+        fn on_retry(&self, name: &str, attempt: u32, _delay: Duration, _error: &str) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("retry:{}:{}", name, attempt));
+        }
+
+        fn on_skip(&self, name: &str, _reason: &str) {
+            self.events.lock().unwrap().push(format!("skip:{}", name));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_listener_events() {
+        init_logger();
+        let (clock, kit) = mock_kit(4);
+        let listener = Arc::new(RecordingListener::default());
+        kit.add_listener(listener.clone());
+        let job_clock = clock.clone();
+
+        kit.add_async_job("ok_job", JobType::Cron("0 0 0 1 1 *".into()), || {
+            std::future::ready(Ok(()))
+        })
+        .unwrap();
+        kit.add_async_job_with_options(
+            "bad_job",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            JobOptions::new().retry(RetryPolicy::fixed(2, Duration::from_secs(1))),
+            || std::future::ready(Err("bad".into())),
+        )
+        .unwrap();
+        kit.add_async_job_with_options(
+            "busy_job",
+            JobType::Cron("0 0 0 1 1 *".into()),
+            JobOptions::new().overlap(OverlapPolicy::Skip),
+            move || {
+                let sleep = job_clock.sleep(Duration::from_secs(2));
+                async move {
+                    sleep.await;
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+
+        kit.trigger_now("ok_job");
+        kit.trigger_now("bad_job");
+        kit.trigger_now("busy_job");
+        clock.run_for(Duration::from_millis(500)).await;
+        kit.trigger_now("busy_job");
+        clock.run_for(Duration::from_secs(2)).await;
+
+        let events = listener.events.lock().unwrap().clone();
+        for expected in [
+            "start:ok_job",
+            "success:ok_job",
+            "start:bad_job",
+            "retry:bad_job:1",
+            "failure:bad_job",
+            "skip:busy_job",
+            "success:busy_job",
+        ] {
+            assert!(
+                events.iter().any(|e| e == expected),
+                "缺少事件 {}: {:?}",
+                expected,
+                events
+            );
+        }
+        assert!(!events.iter().any(|e| e == "success:bad_job"));
+        kit.stop_all();
+    }
+
+    #[test]
+    fn test_timezone_next_fire() {
+        use std::str::FromStr;
+
+        let schedule = cron::Schedule::from_str("0 0 9 * * *").unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            JobTimeZone::Utc.next_fire(&schedule, after),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap())
+        );
+        assert_eq!(
+            JobTimeZone::hours(8).unwrap().next_fire(&schedule, after),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap())
+        );
+        assert_eq!(
+            JobTimeZone::hours(-5).unwrap().next_fire(&schedule, after),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 14, 0, 0).unwrap())
+        );
+        assert!(JobTimeZone::Local.next_fire(&schedule, after).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_timezone() {
+        let kit = JobKit::new(1);
+        let result = kit.add_job_with_options(
+            "bad_tz",
+            JobType::Cron("0 0 9 * * *".into()),
+            JobOptions::new().timezone(JobTimeZone::Fixed(100_000)),
+            || Ok(()),
+        );
+        assert!(result.is_err());
+
+        assert_eq!(JobTimeZone::hours(-23), Ok(JobTimeZone::Fixed(-23 * 3600)));
+        assert!(JobTimeZone::hours(24).is_err());
+        assert!(JobTimeZone::hours(-100).is_err());
+        assert!(
+            JobTimeZone::hours(i32::MAX).is_err(),
+            "溢出时应返回错误而不是 panic"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zero_interval_rejected() {
+        let kit = JobKit::new(1);
+        let err = kit
+            .add_job("zero", JobType::Interval(Duration::ZERO), || Ok(()))
+            .unwrap_err();
+        assert!(err.contains("greater than zero"), "{}", err);
+        let delayed = JobType::DelayedInterval {
+            delay: Duration::from_secs(1),
+            interval: Duration::ZERO,
+        };
+        assert!(kit.add_job("zero", delayed, || Ok(())).is_err());
+        assert!(kit.status("zero").is_none());
+    }
+
+    fn counting_task(
+        counter: &Arc<AtomicUsize>,
+    ) -> impl Fn() -> std::future::Ready<JobResult> + Send + Sync + 'static {
+        let counter = counter.clone();
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            std::future::ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_one_shot_jobs() {
+        init_logger();
+        let (clock, kit) = mock_kit(2);
+        let at_counter = Arc::new(AtomicUsize::new(0));
+        let delay_counter = Arc::new(AtomicUsize::new(0));
+
+        let at = start() + chrono::Duration::seconds(15);
+        kit.add_async_job("at_job", JobType::At(at), counting_task(&at_counter))
+            .unwrap();
+        kit.add_async_job(
+            "delay_job",
+            JobType::Delay(Duration::from_secs(10)),
+            counting_task(&delay_counter),
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_secs(5)).await;
+        assert_eq!(at_counter.load(Ordering::SeqCst), 0);
+        assert_eq!(delay_counter.load(Ordering::SeqCst), 0);
+        assert_eq!(kit.status("at_job").unwrap().next_run, Some(at));
+
+        clock.run_for(Duration::from_secs(15)).await;
+        assert_eq!(at_counter.load(Ordering::SeqCst), 1);
+        assert_eq!(delay_counter.load(Ordering::SeqCst), 1);
+        assert_eq!(kit.get_history("at_job").unwrap()[0].timestamp, at);
+        assert_eq!(
+            kit.get_history("delay_job").unwrap()[0].timestamp,
+            start() + chrono::Duration::seconds(10)
+        );
+        let status = kit.status("at_job").unwrap();
+        assert_eq!(status.state, JobState::Finished);
+        assert!(status.next_run.is_none());
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_delayed_interval() {
+        init_logger();
+        let (clock, kit) = mock_kit(2);
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        kit.add_async_job(
+            "delayed_interval",
+            JobType::DelayedInterval {
+                delay: Duration::from_secs(30),
+                interval: Duration::from_secs(5),
+            },
+            counting_task(&counter),
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_secs(29)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), 0, "初始延迟内不应执行");
+        clock.run_for(Duration::from_secs(13)).await;
+        let offsets: Vec<i64> = kit
+            .get_history("delayed_interval")
+            .unwrap()
+            .iter()
+            .map(|r| (r.timestamp - start()).num_seconds())
+            .collect();
+        assert_eq!(offsets, vec![30, 35, 40]);
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_max_runs_and_end_at() {
+        init_logger();
+        let (clock, kit) = mock_kit(2);
+        let bounded = Arc::new(AtomicUsize::new(0));
+        let ending = Arc::new(AtomicUsize::new(0));
+
+        kit.add_async_job_with_options(
+            "bounded_job",
+            JobType::Interval(Duration::from_secs(1)),
+            JobOptions::new().max_runs(3),
+            counting_task(&bounded),
+        )
+        .unwrap();
+        kit.add_async_job_with_options(
+            "ending_job",
+            JobType::Interval(Duration::from_secs(1)),
+            JobOptions::new().end_at(start() + chrono::Duration::milliseconds(4500)),
+            counting_task(&ending),
+        )
+        .unwrap();
+
+        clock.run_for(Duration::from_secs(10)).await;
+        assert_eq!(bounded.load(Ordering::SeqCst), 3);
+        assert_eq!(kit.status("bounded_job").unwrap().state, JobState::Finished);
+        assert_eq!(ending.load(Ordering::SeqCst), 5, "截止时间后不应再执行");
+        assert_eq!(kit.status("ending_job").unwrap().state, JobState::Finished);
+        kit.stop_all();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_shutdown_drains_in_flight_runs() {
+        init_logger();
+        let kit = JobKit::new(2);
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+
+        kit.add_job(
+            "drain_job",
+            JobType::Interval(Duration::from_millis(50)),
+            move || {
+                std::thread::sleep(Duration::from_millis(200));
+                counter_clone.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            },
+        )
+        .unwrap();
+        kit.add_job("idle_job", JobType::Cron("0 0 0 1 1 *".into()), || Ok(()))
+            .unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let report = kit.shutdown(Duration::from_secs(2)).await;
+        assert_eq!(report.completed, vec!["drain_job".to_string()]);
+        assert!(report.cancelled.is_empty());
+
+        let count = counter.load(Ordering::SeqCst);
+        assert!(count >= 1, "进行中的任务应执行完毕");
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(counter.load(Ordering::SeqCst), count, "关闭后不应再调度");
+
+        assert!(kit.list_jobs().is_empty());
+        assert!(!kit.trigger_now("drain_job"));
+        assert!(kit
+            .add_job("late_job", JobType::Delay(Duration::ZERO), || Ok(()))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_cancels_after_deadline() {
+        init_logger();
+        let kit = JobKit::new(2);
+        let finished = Arc::new(AtomicUsize::new(0));
+        let finished_clone = finished.clone();
+
+        kit.add_async_job("long_job", JobType::Cron("0 0 0 1 1 *".into()), move || {
+            let finished = finished_clone.clone();
+            async move {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                finished.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        })
+        .unwrap();
+
+        kit.trigger_now("long_job");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let start = std::time::Instant::now();
+        let report = kit.shutdown(Duration::from_millis(100)).await;
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert_eq!(report.cancelled, vec!["long_job".to_string()]);
+        assert!(report.completed.is_empty());
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(finished.load(Ordering::SeqCst), 0);
+    }
 }
@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::jobkit::{JobKit, JobOptions, JobType, RetryPolicy, Workflow};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    const YEARLY: &str = "0 0 0 1 1 *";
+
+    fn record_step(
+        log: &Arc<Mutex<Vec<String>>>,
+        name: &'static str,
+    ) -> impl Fn() -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync + 'static
+    {
+        let log = log.clone();
+        move || {
+            log.lock().unwrap().push(name.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_workflow_runs_in_order() {
+        let kit = JobKit::new(4);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let upload_log = log.clone();
+
+        let workflow = Workflow::new("nightly")
+            .step("fetch", &[], record_step(&log, "fetch"))
+            .step("transform", &["fetch"], record_step(&log, "transform"))
+            .step("compress", &["transform"], record_step(&log, "compress"))
+            .async_step("upload", &["compress"], move || {
+                let log = upload_log.clone();
+                async move {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    log.lock().unwrap().push("upload".to_string());
+                    Ok(())
+                }
+            });
+        kit.add_workflow(workflow, JobType::Cron(YEARLY.into()), JobOptions::new())
+            .unwrap();
+
+        assert!(kit.trigger_now("nightly"));
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["fetch", "transform", "compress", "upload"]
+        );
+        for step in ["fetch", "transform", "compress", "upload"] {
+            let history = kit.get_history(&format!("nightly/{}", step)).unwrap();
+            assert_eq!(history.len(), 1);
+            assert!(history[0].result.is_ok());
+        }
+        assert!(kit.get_history("nightly").unwrap()[0].result.is_ok());
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_workflow_failure_skips_downstream() {
+        let kit = JobKit::new(1);
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        // a -> b, a -> c, (b, c) -> d, c 失败则 d 跳过
+        let workflow = Workflow::new("diamond")
+            .step("a", &[], record_step(&log, "a"))
+            .step("b", &["a"], record_step(&log, "b"))
+            .step_with_options(
+                "c",
+                &["a"],
+                JobOptions::new().retry(RetryPolicy::fixed(2, Duration::from_millis(10))),
+                || Err("c failed".into()),
+            )
+            .step("d", &["b", "c"], record_step(&log, "d"));
+        kit.add_workflow(workflow, JobType::Cron(YEARLY.into()), JobOptions::new())
+            .unwrap();
+
+        kit.trigger_now("diamond");
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let mut ran = log.lock().unwrap().clone();
+        ran.sort();
+        assert_eq!(ran, vec!["a", "b"]);
+
+        let c = kit.get_history("diamond/c").unwrap();
+        assert_eq!(c[0].attempts, 2);
+        assert!(!c[0].skipped);
+
+        let d = kit.get_history("diamond/d").unwrap();
+        assert_eq!(d.len(), 1);
+        assert!(d[0].skipped);
+        assert!(d[0].result.is_err());
+
+        let root = kit.get_history("diamond").unwrap();
+        assert!(root[0].result.as_ref().unwrap_err().contains("diamond/c"));
+        kit.stop_all();
+    }
+
+    #[tokio::test]
+    async fn test_workflow_validation() {
+        let kit = JobKit::new(2);
+        let cron = || JobType::Cron(YEARLY.into());
+
+        let cycle =
+            Workflow::new("cycle")
+                .step("a", &["b"], || Ok(()))
+                .step("b", &["a"], || Ok(()));
+        assert!(kit.add_workflow(cycle, cron(), JobOptions::new()).is_err());
+
+        let unknown = Workflow::new("unknown").step("a", &["missing"], || Ok(()));
+        assert!(kit
+            .add_workflow(unknown, cron(), JobOptions::new())
+            .is_err());
+
+        let duplicate = Workflow::new("duplicate")
+            .step("a", &[], || Ok(()))
+            .step("a", &[], || Ok(()));
+        assert!(kit
+            .add_workflow(duplicate, cron(), JobOptions::new())
+            .is_err());
+
+        assert!(kit
+            .add_workflow(Workflow::new("empty"), cron(), JobOptions::new())
+            .is_err());
+        assert!(kit.list_jobs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stop_workflow_stops_steps() {
+        let kit = JobKit::new(2);
+        let workflow =
+            Workflow::new("pipeline")
+                .step("a", &[], || Ok(()))
+                .step("b", &["a"], || Ok(()));
+        kit.add_workflow(workflow, JobType::Cron(YEARLY.into()), JobOptions::new())
+            .unwrap();
+        assert_eq!(kit.list_jobs().len(), 3);
+
+        assert!(kit.stop("pipeline"));
+        assert!(kit.list_jobs().is_empty());
+    }
+}
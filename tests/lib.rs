@@ -1,3 +1,12 @@
+mod cache_builder_test;
+mod cache_concurrent_test;
+mod cache_disk_test;
+mod cache_expiry_test;
+mod cache_loading_test;
+mod cache_memoize_test;
+mod cache_ops_test;
+mod cache_policy_test;
+mod cache_stats_test;
 mod cache_test;
 mod cache_time_test;
 mod config_test;
@@ -5,6 +14,7 @@ mod url_test;
 
 mod conv_test;
 
+mod clock_test;
 mod collection_test;
 mod compress_test;
 mod concurrent_test;
@@ -17,7 +27,13 @@ mod hash_test;
 mod http_test;
 mod id_test;
 mod io_test;
+mod job_clock_test;
+mod job_config_test;
+mod job_history_test;
+mod job_lock_test;
+mod job_store_test;
 mod job_test;
+mod job_workflow_test;
 mod json_test;
 mod log_test;
 mod net_test;
@@ -27,5 +43,6 @@ mod process_demo;
 mod process_test;
 mod rand_test;
 mod reg_test;
+mod registry_test;
 mod single_test;
 mod string_test;
@@ -26,13 +26,13 @@ mod tests {
     #[test]
     fn test_is_port_in_use() {
         let port = 65534; // unlikely to be in use
-        assert_eq!(is_port_in_use(port), false);
+        assert!(!is_port_in_use(port));
         println!("Port {} is in use: {}", port, is_port_in_use(port))
     }
 
     #[test]
     fn test_is_udp_port_in_use() {
         let port = 65533; // unlikely to be in use
-        assert_eq!(is_udp_port_in_use(port), false);
+        assert!(!is_udp_port_in_use(port));
     }
 }
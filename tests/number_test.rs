@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use rovkit::numberkit::*;
 
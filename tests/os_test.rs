@@ -28,7 +28,7 @@ mod tests {
         );
     }
 
-    #[cfg(any(target_os = "windows"))]
+    #[cfg(target_os = "windows")]
     #[test]
     fn test_disk_usage_window() {
         let usage = get_disk_usage("C:\\");
@@ -15,7 +15,7 @@ mod tests {
     fn test_random_float() {
         for _ in 0..100 {
             let v = random_float(0.0, 1.0);
-            assert!(v >= 0.0 && v < 1.0);
+            assert!((0.0..1.0).contains(&v));
             println!("random_float = {}", v)
         }
     }
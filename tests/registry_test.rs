@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod tests {
+    use rovkit::registrykit::{self, Lifetime, Registry};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    trait Store: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    struct MemoryStore;
+
+    impl Store for MemoryStore {
+        fn name(&self) -> String {
+            "memory".to_string()
+        }
+    }
+
+    struct MockStore;
+
+    impl Store for MockStore {
+        fn name(&self) -> String {
+            "mock".to_string()
+        }
+    }
+
+    struct Service {
+        store: Arc<dyn Store>,
+    }
+
+    #[test]
+    fn test_register_by_type() {
+        let mut registry = Registry::new();
+        assert!(!registry.contains::<dyn Store>());
+        assert!(registry.resolve::<dyn Store>().is_err());
+
+        registry.register::<dyn Store>(Lifetime::Singleton, |_| Ok(Arc::new(MemoryStore)));
+        assert!(registry.contains::<dyn Store>());
+        assert_eq!(registry.resolve::<dyn Store>().unwrap().name(), "memory");
+
+        assert!(registry.remove::<dyn Store>());
+        assert!(!registry.remove::<dyn Store>());
+    }
+
+    #[test]
+    fn test_register_named() {
+        let mut registry = Registry::new();
+        registry
+            .register_named::<dyn Store>("memory", Lifetime::Singleton, |_| {
+                Ok(Arc::new(MemoryStore))
+            })
+            .register_named::<dyn Store>("mock", Lifetime::Singleton, |_| Ok(Arc::new(MockStore)));
+
+        assert_eq!(
+            registry
+                .resolve_named::<dyn Store>("memory")
+                .unwrap()
+                .name(),
+            "memory"
+        );
+        assert_eq!(
+            registry.resolve_named::<dyn Store>("mock").unwrap().name(),
+            "mock"
+        );
+        assert!(registry.contains_named::<dyn Store>("mock"));
+        assert!(!registry.contains::<dyn Store>());
+        let err = registry.resolve_named::<dyn Store>("disk").err().unwrap();
+        assert!(err.contains("disk"), "{}", err);
+    }
+
+    #[test]
+    fn test_lifetimes_and_lazy() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let mut registry = Registry::new();
+        let counter = created.clone();
+        registry.register::<String>(Lifetime::Singleton, move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(Arc::new("config".to_string()))
+        });
+        let counter = created.clone();
+        registry.register::<usize>(Lifetime::Transient, move |_| {
+            Ok(Arc::new(counter.fetch_add(1, Ordering::SeqCst)))
+        });
+        assert_eq!(created.load(Ordering::SeqCst), 0, "注册时不创建");
+
+        let a = registry.resolve::<String>().unwrap();
+        let b = registry.resolve::<String>().unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+
+        let x = registry.resolve::<usize>().unwrap();
+        let y = registry.resolve::<usize>().unwrap();
+        assert_ne!(x, y);
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_failed_singleton_retries() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+        let mut registry = Registry::new();
+        registry.register::<u32>(Lifetime::Singleton, move |_| {
+            match counter.fetch_add(1, Ordering::SeqCst) {
+                0 => Err("not ready".to_string()),
+                _ => Ok(Arc::new(7)),
+            }
+        });
+        assert_eq!(registry.resolve::<u32>().err().unwrap(), "not ready");
+        assert_eq!(*registry.resolve::<u32>().unwrap(), 7);
+        assert_eq!(*registry.resolve::<u32>().unwrap(), 7);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_dependency_and_swap() {
+        let mut registry = Registry::new();
+        registry
+            .register::<dyn Store>(Lifetime::Singleton, |_| Ok(Arc::new(MemoryStore)))
+            .register::<Service>(Lifetime::Transient, |r| {
+                Ok(Arc::new(Service {
+                    store: r.resolve()?,
+                }))
+            });
+        assert_eq!(
+            registry.resolve::<Service>().unwrap().store.name(),
+            "memory"
+        );
+
+        // 测试中替换实现
+        registry.register_instance::<dyn Store>(Arc::new(MockStore));
+        assert_eq!(registry.resolve::<Service>().unwrap().store.name(), "mock");
+
+        registry.remove::<dyn Store>();
+        let err = registry.resolve::<Service>().err().unwrap();
+        assert!(err.contains("not registered"), "{}", err);
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let mut registry = Registry::new();
+        registry
+            .register::<u8>(Lifetime::Singleton, |r| {
+                r.resolve::<u16>()?;
+                Ok(Arc::new(1))
+            })
+            .register::<u16>(Lifetime::Transient, |r| {
+                r.resolve::<u8>()?;
+                Ok(Arc::new(2))
+            })
+            .register_named::<u32>("self", Lifetime::Singleton, |r| {
+                r.resolve_named::<u32>("self")
+            });
+
+        assert_eq!(
+            registry.resolve::<u8>().err().unwrap(),
+            "circular dependency: u8 -> u16 -> u8"
+        );
+        assert_eq!(
+            registry.resolve::<u16>().err().unwrap(),
+            "circular dependency: u16 -> u8 -> u16"
+        );
+        assert_eq!(
+            registry.resolve_named::<u32>("self").err().unwrap(),
+            "circular dependency: u32(self) -> u32(self)"
+        );
+    }
+
+    #[test]
+    fn test_cross_thread_cycle() {
+        let barrier = Arc::new(Barrier::new(2));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut registry = Registry::new();
+        for (a, b) in [("a", "b"), ("b", "a")] {
+            let (barrier, calls) = (barrier.clone(), calls.clone());
+            registry.register_named::<u8>(a, Lifetime::Singleton, move |r| {
+                // 两个线程都先拿到各自的单例再去解析对方
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    barrier.wait();
+                }
+                r.resolve_named::<u8>(b)?;
+                Ok(Arc::new(1))
+            });
+        }
+
+        let errors: Vec<String> = thread::scope(|scope| {
+            let handles: Vec<_> = ["a", "b"]
+                .into_iter()
+                .map(|name| scope.spawn(|| registry.resolve_named::<u8>(name).err().unwrap()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        for err in errors {
+            assert!(err.starts_with("circular dependency: u8("), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_concurrent_singleton() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = created.clone();
+        let mut registry = Registry::new();
+        registry.register::<dyn Store>(Lifetime::Singleton, move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(std::time::Duration::from_millis(300));
+            Ok(Arc::new(MemoryStore))
+        });
+
+        let stores: Vec<Arc<dyn Store>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..2)
+                .map(|_| scope.spawn(|| registry.resolve::<dyn Store>().unwrap()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(Arc::ptr_eq(&stores[0], &stores[1]));
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_global_registry() {
+        registrykit::global()
+            .write()
+            .unwrap()
+            .register_named::<String>("greeting", Lifetime::Singleton, |_| {
+                Ok(Arc::new("hello".to_string()))
+            });
+        let registry = registrykit::global().read().unwrap();
+        assert_eq!(
+            *registry.resolve_named::<String>("greeting").unwrap(),
+            "hello"
+        );
+        drop(registry);
+
+        registrykit::global()
+            .write()
+            .unwrap()
+            .register_instance::<i128>(Arc::new(42));
+        assert_eq!(*registrykit::resolve::<i128>().unwrap(), 42);
+    }
+}
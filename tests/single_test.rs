@@ -50,4 +50,107 @@ mod tests {
             print!("config: {:?}", c);
         }
     }
+
+    #[test]
+    fn test_single_try_init() {
+        static PORT: Single<u16> = Single::new();
+        let err = PORT.get_or_try_init(|| "abc".parse::<u16>()).unwrap_err();
+        println!("init error: {}", err);
+        assert!(PORT.get().is_none(), "失败后不应保存");
+
+        // 下次调用重试
+        let port = PORT.get_or_try_init(|| "8080".parse::<u16>()).unwrap();
+        assert_eq!(*port.lock().unwrap(), 8080);
+        let port = PORT.get_or_try_init(|| "9090".parse::<u16>()).unwrap();
+        assert_eq!(*port.lock().unwrap(), 8080);
+    }
+
+    #[tokio::test]
+    async fn test_single_async_init() {
+        static NAME: Single<String> = Single::new();
+        let mut tasks = tokio::task::JoinSet::new();
+        for i in 0..4 {
+            tasks.spawn(async move {
+                let name = NAME
+                    .get_or_init_async(|| async move {
+                        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                        format!("task-{}", i)
+                    })
+                    .await;
+                name.lock().unwrap().clone()
+            });
+        }
+        let names: Vec<String> = tasks.join_all().await;
+        assert!(names.iter().all(|name| *name == names[0]), "只初始化一次");
+
+        static FAILING: Single<String> = Single::new();
+        let result = FAILING
+            .get_or_try_init_async(|| async { Err::<String, _>("offline") })
+            .await;
+        assert!(result.is_err());
+        assert!(FAILING.get().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_single_mixed_init_once() {
+        static VALUE: Single<String> = Single::new();
+        static INITS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let task = tokio::spawn(async {
+            let value = VALUE
+                .get_or_init_async(|| async {
+                    INITS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    "async".to_string()
+                })
+                .await;
+            value.lock().unwrap().clone()
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        // 同步调用方等待异步初始化完成，不会再初始化一次
+        let sync = std::thread::spawn(|| {
+            let value = VALUE.get_or_init(|| {
+                INITS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                "sync".to_string()
+            });
+            value.lock().unwrap().clone()
+        });
+        assert_eq!(task.await.unwrap(), "async");
+        assert_eq!(sync.join().unwrap(), "async");
+        assert_eq!(INITS.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_single_rw() {
+        static SETTINGS: SingleRw<Vec<String>> = SingleRw::new();
+        let settings = SETTINGS.get_or_init(|| vec!["a".to_string()]);
+        {
+            let r1 = settings.read().unwrap();
+            let r2 = settings.read().unwrap();
+            assert_eq!(r1.len(), r2.len());
+        }
+        settings.write().unwrap().push("b".to_string());
+        assert_eq!(SETTINGS.get().unwrap().read().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_single_reset() {
+        static COUNTER: Single<i32> = Single::new();
+        let counter = COUNTER.get_or_init(|| 1);
+        COUNTER.reset();
+        assert!(COUNTER.get().is_none());
+        COUNTER.get_or_init(|| 2);
+        // 旧引用看到新值
+        assert_eq!(*counter.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_singleton_rwlock() {
+        struct Flags {
+            enabled: bool,
+        }
+
+        singleton!(FLAGS: RwLock<Flags> = Flags { enabled: false });
+        Flags::single().write().unwrap().enabled = true;
+        assert!(FLAGS.read().unwrap().enabled);
+    }
 }